@@ -4,9 +4,20 @@ use hdk::prelude::*;
 
 mod tictactoe;
 
-use tictactoe::TicTacToe;
+use tictactoe::{TicTacToe, TicTacToeOptions};
 
-entry_defs![GameMoveEntry::entry_def(), GameEntry::entry_def()];
+entry_defs![
+    GameMoveEntry::entry_def(),
+    GameEntry::entry_def(),
+    MatchEntry::entry_def(),
+    TournamentEntry::entry_def(),
+    TournamentRound::entry_def(),
+    GameResultEntry::entry_def(),
+    Invitation::entry_def(),
+    GameRequest::entry_def(),
+    RatingChangeEntry::entry_def(),
+    Path::entry_def()
+];
 
 #[hdk_extern]
 fn init(_: ()) -> ExternResult<InitCallbackResult> {
@@ -25,6 +36,25 @@ fn create_tictactoe_game(rival: AgentPubKeyB64) -> ExternResult<EntryHashB64> {
     Ok(hash.into())
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateGameWithOptionsInput {
+    rival: AgentPubKeyB64,
+    options: TicTacToeOptions,
+}
+
+#[hdk_extern]
+fn create_tictactoe_game_with_options(
+    input: CreateGameWithOptionsInput,
+) -> ExternResult<EntryHashB64> {
+    let hash = create_game_with_options::<TicTacToe>(
+        vec![input.rival, agent_info()?.agent_latest_pubkey.into()],
+        GameSettings::default(),
+        input.options,
+    )?;
+
+    Ok(hash.into())
+}
+
 #[hdk_extern]
 fn get_game_state(game_hash: EntryHashB64) -> ExternResult<TicTacToe> {
     hc_mixin_turn_based_game::get_game_state::<TicTacToe>(game_hash.into())
@@ -43,3 +73,5 @@ fn get_winner(game_hash: EntryHashB64) -> ExternResult<Option<u8>> {
 }
 
 mixin_turn_based_game!(TicTacToe);
+
+mixin_ratings!();
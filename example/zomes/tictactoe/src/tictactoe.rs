@@ -1,20 +1,33 @@
-use hc_mixin_turn_based_game::{GameStatus, TurnBasedGame};
+use hc_mixin_turn_based_game::{GameOutcome, GameStatus, OutcomeReason, TurnBasedGame};
 use hdk::prelude::holo_hash::AgentPubKeyB64;
 use hdk::prelude::*;
 
-pub const BOARD_SIZE: usize = 3;
+pub const DEFAULT_BOARD_SIZE: usize = 3;
+pub const MAX_BOARD_SIZE: usize = 8;
 
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
 pub struct TicTacToe {
     pub player_1: (AgentPubKey, Vec<Piece>),
     pub player_2: (AgentPubKey, Vec<Piece>),
-    pub player_resigned: Option<AgentPubKeyB64>,
+    pub board_size: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct TicTacToeOptions {
+    pub board_size: usize,
+}
+
+impl Default for TicTacToeOptions {
+    fn default() -> Self {
+        TicTacToeOptions {
+            board_size: DEFAULT_BOARD_SIZE,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
 pub enum TicTacToeMove {
     Place(Piece),
-    Resign,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -24,8 +37,8 @@ pub struct Piece {
 }
 
 impl Piece {
-    fn is_in_bounds(&self) -> ExternResult<()> {
-        if self.x < BOARD_SIZE && self.y < BOARD_SIZE
+    fn is_in_bounds(&self, game_state: &TicTacToe) -> ExternResult<()> {
+        if self.x < game_state.board_size && self.y < game_state.board_size
         // no need to check > 0 as usize is always positive
         {
             Ok(())
@@ -46,6 +59,7 @@ impl Piece {
 
 impl TurnBasedGame for TicTacToe {
     type GameMove = TicTacToeMove;
+    type GameOptions = TicTacToeOptions;
 
     fn min_players() -> Option<usize> {
         Some(2)
@@ -55,11 +69,24 @@ impl TurnBasedGame for TicTacToe {
         Some(2)
     }
 
-    fn initial(players: Vec<AgentPubKeyB64>) -> Self {
+    fn validate_options(
+        options: &TicTacToeOptions,
+        _players: &Vec<AgentPubKeyB64>,
+    ) -> ExternResult<()> {
+        match options.board_size >= DEFAULT_BOARD_SIZE && options.board_size <= MAX_BOARD_SIZE {
+            true => Ok(()),
+            false => Err(WasmError::Guest(format!(
+                "The board size must be between {} and {}",
+                DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE
+            ))),
+        }
+    }
+
+    fn initial(players: Vec<AgentPubKeyB64>, options: TicTacToeOptions) -> Self {
         TicTacToe {
             player_1: (players[0].clone().into(), vec![]),
             player_2: (players[1].clone().into(), vec![]),
-            player_resigned: None,
+            board_size: options.board_size,
         }
     }
 
@@ -72,7 +99,7 @@ impl TurnBasedGame for TicTacToe {
 
         match game_move {
             TicTacToeMove::Place(piece) => {
-                piece.is_in_bounds()?;
+                piece.is_in_bounds(&self)?;
                 piece.is_empty(&self)?;
 
                 match author.eq(&self.player_1.0.clone().into()) {
@@ -80,27 +107,42 @@ impl TurnBasedGame for TicTacToe {
                     false => game.player_2.1.push(piece.clone()),
                 }
             }
-            TicTacToeMove::Resign => game.player_resigned = Some(author),
         }
 
         Ok(game)
     }
 
     fn status(&self) -> GameStatus {
-        if let Some(_) = self.player_resigned.clone() {
-            return GameStatus::Finished;
-        }
-
-        if let Some(_) = self.winner() {
+        if self.winner().is_some() || self.is_board_full() {
             return GameStatus::Finished;
         }
         return GameStatus::Ongoing;
     }
+
+    fn outcome(&self, players: &Vec<AgentPubKeyB64>) -> Option<GameOutcome> {
+        let winner = match self.winner() {
+            Some(0) => self.player_1.0.clone(),
+            Some(_) => self.player_2.0.clone(),
+            None if self.is_board_full() => {
+                return Some(GameOutcome::draw(
+                    players,
+                    OutcomeReason::GameRules(String::from("Board full")),
+                ))
+            }
+            None => return None,
+        };
+
+        Some(GameOutcome::victory(
+            vec![winner.into()],
+            players,
+            OutcomeReason::GameRules(format!("{} in a row", self.board_size)),
+        ))
+    }
 }
 
 impl TicTacToe {
-    pub fn to_dense(&self) -> [[u8; 8]; 8] {
-        let mut board = [[0u8; 8]; 8];
+    pub fn to_dense(&self) -> [[u8; MAX_BOARD_SIZE]; MAX_BOARD_SIZE] {
+        let mut board = [[0u8; MAX_BOARD_SIZE]; MAX_BOARD_SIZE];
         self.player_1.1.iter().for_each(|piece| {
             board[piece.x][piece.y] = 1;
         });
@@ -110,16 +152,20 @@ impl TicTacToe {
         board
     }
 
+    pub fn is_board_full(&self) -> bool {
+        self.player_1.1.len() + self.player_2.1.len() >= self.board_size * self.board_size
+    }
+
     pub fn winner(&self) -> Option<u8> {
         let board = self.to_dense();
 
         // check if this resulted in a player victory
         let mut diag_down = 0;
         let mut diag_up = 0;
-        let mut across = [0; 3];
-        let mut down = [0; 3];
-        for x in 0..BOARD_SIZE {
-            for y in 0..BOARD_SIZE {
+        let mut across = [0; MAX_BOARD_SIZE];
+        let mut down = [0; MAX_BOARD_SIZE];
+        for x in 0..self.board_size {
+            for y in 0..self.board_size {
                 let delta = match board[x][y] {
                     1 => 1,
                     2 => -1,
@@ -132,20 +178,20 @@ impl TicTacToe {
                     diag_down += delta;
                 }
                 //diag up  e.g. /
-                else if x == (BOARD_SIZE - 1 - y) {
+                if x == (self.board_size - 1 - y) {
                     diag_up += delta;
                 }
             }
         }
-        let player_1_victory = across.iter().any(|e| *e == (BOARD_SIZE as i32))
-            || down.iter().any(|e| *e == (BOARD_SIZE as i32))
-            || diag_down == (BOARD_SIZE as i32)
-            || diag_up == (BOARD_SIZE as i32);
-
-        let player_2_victory = across.iter().any(|e| *e == (-1 * BOARD_SIZE as i32))
-            || down.iter().any(|e| *e == (-1 * BOARD_SIZE as i32))
-            || diag_down == (-1 * BOARD_SIZE as i32)
-            || diag_up == (-1 * BOARD_SIZE as i32);
+        let player_1_victory = across.iter().any(|e| *e == (self.board_size as i32))
+            || down.iter().any(|e| *e == (self.board_size as i32))
+            || diag_down == (self.board_size as i32)
+            || diag_up == (self.board_size as i32);
+
+        let player_2_victory = across.iter().any(|e| *e == (-1 * self.board_size as i32))
+            || down.iter().any(|e| *e == (-1 * self.board_size as i32))
+            || diag_down == (-1 * self.board_size as i32)
+            || diag_up == (-1 * self.board_size as i32);
 
         if player_1_victory {
             return Some(0);
@@ -0,0 +1,63 @@
+#![no_main]
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use common::{Add, Countdown, CountdownOptions};
+use hc_mixin_turn_based_game::{simulated_players, GameSimulator, GameStatus};
+use libfuzzer_sys::fuzz_target;
+
+/**
+ * Plays arbitrary sequences of moves, checking that the simulator never panics, that rejected moves
+ * don't change the game, and that accepted moves follow the turn order and the rules of the game
+ *
+ * The first two bytes choose the number of players and the target, and each following pair of bytes
+ * is a move: who makes it, and how much they add, including amounts that the game rejects
+ */
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+
+    let players = simulated_players(2 + data[0] % 3);
+    let target = 1 + data[1] as u32 % 30;
+
+    let mut simulator =
+        GameSimulator::<Countdown>::new_with_options(players.clone(), CountdownOptions { target })
+            .unwrap();
+
+    for chunk in data[2..].chunks_exact(2) {
+        let author = &players[chunk[0] as usize % players.len()];
+        let amount = chunk[1] as u32 % 5;
+
+        let state_before = simulator.state().clone();
+        let moves_before = simulator.moves().len();
+        let was_ongoing = matches!(simulator.status(), GameStatus::Ongoing);
+        let was_allowed = simulator.allowed_movers().contains(author);
+
+        match simulator.play(author, Add(amount)) {
+            Ok(state) => {
+                assert!(was_ongoing && was_allowed);
+                assert!((1..=3).contains(&amount));
+                assert_eq!(state.count, (state_before.count + amount).min(target));
+                assert_eq!(simulator.moves().len(), moves_before + 1);
+            }
+            Err(_) => {
+                assert_eq!(simulator.state(), &state_before);
+                assert_eq!(simulator.moves().len(), moves_before);
+            }
+        }
+
+        match simulator.status() {
+            GameStatus::Finished => {
+                let outcome = simulator.outcome().unwrap();
+
+                // Whoever reaches the target wins
+                if was_ongoing {
+                    assert_eq!(outcome.winners, vec![author.clone()]);
+                }
+            }
+            GameStatus::Ongoing => assert!(simulator.outcome().is_none()),
+        }
+    }
+});
@@ -0,0 +1,169 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    game_move::{commit_move, get_commit_move, GameMoveEntry, MoveType},
+    move_context::MoveContext,
+    turn_based_game::TurnBasedGame,
+};
+
+/** Public handlers */
+
+/**
+ * Commits to the given game move without revealing it, publishing only the hash of the move and a secret salt
+ *
+ * The move and the salt must be kept secret by the caller until they are revealed with `reveal_move`
+ */
+pub fn commit_hidden_move<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    game_move: G::GameMove,
+    salt: Vec<u8>,
+) -> ExternResult<HeaderHashB64> {
+    let move_bytes: SerializedBytes = game_move
+        .try_into()
+        .or(Err(WasmError::Guest("Couldn't serialize game move".into())))?;
+
+    let commitment = move_commitment(&move_bytes, &salt)?;
+
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::CommitMove { commitment },
+    )
+}
+
+/**
+ * Reveals the game move committed to in the given commit move, applying it to the game
+ */
+pub fn reveal_move<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    commit_move_hash: HeaderHashB64,
+    game_move: G::GameMove,
+    salt: Vec<u8>,
+) -> ExternResult<HeaderHashB64> {
+    let move_bytes: SerializedBytes = game_move
+        .try_into()
+        .or(Err(WasmError::Guest("Couldn't serialize game move".into())))?;
+
+    // Fail early without committing anything if the reveal doesn't match the commitment
+    let commit = get_commit_move(&game_hash, &commit_move_hash)?.ok_or(WasmError::Guest(
+        "The commit move is not a move of this game".into(),
+    ))?;
+
+    verify_reveal_matches_commit(&commit, &move_bytes, &salt)?;
+
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::RevealMove {
+            commit_move_hash,
+            game_move: move_bytes,
+            salt,
+        },
+    )
+}
+
+/** Helper functions */
+
+/**
+ * Computes the commitment for the given serialized game move and salt
+ */
+pub fn move_commitment(move_bytes: &SerializedBytes, salt: &Vec<u8>) -> ExternResult<Vec<u8>> {
+    let mut input = move_bytes.bytes().clone();
+    input.extend(salt);
+
+    sha256(input)
+}
+
+/**
+ * SHA-256 hash of the given bytes, computed in the zome instead of in the host,
+ * so that commitments can also be verified outside of a conductor, as the `GameSimulator` does
+ */
+pub(crate) fn sha256(bytes: Vec<u8>) -> ExternResult<Vec<u8>> {
+    Ok(Sha256::digest(&bytes).to_vec())
+}
+
+/**
+ * Returns error if the given commit move is not an unrevealed commitment of the author to the revealed game move
+ */
+pub(crate) fn verify_can_reveal(
+    commit: &GameMoveEntry,
+    commit_move_hash: &HeaderHashB64,
+    context: &MoveContext,
+    author: &AgentPubKeyB64,
+    move_bytes: &SerializedBytes,
+    salt: &Vec<u8>,
+) -> ExternResult<()> {
+    if !commit.author_pub_key.eq(author) {
+        return Err(WasmError::Guest(
+            "Only the author of a commit move can reveal it".into(),
+        ));
+    }
+
+    // Reveals reverted by a takeback reopen their commitment, so the move can be revealed again
+    if context.open_commitments.get(author) != Some(commit_move_hash) {
+        return Err(WasmError::Guest(
+            "The commit move is not the open commitment of its author: it has already been revealed, or it has been taken back".into(),
+        ));
+    }
+
+    verify_reveal_matches_commit(commit, move_bytes, salt)
+}
+
+/**
+ * Returns error if the commitment is not a SHA-256 hash, or if the author already has a commitment that they haven't revealed
+ */
+pub(crate) fn verify_can_commit(
+    context: &MoveContext,
+    author: &AgentPubKeyB64,
+    commitment: &Vec<u8>,
+) -> ExternResult<()> {
+    if commitment.len() != 32 {
+        return Err(WasmError::Guest(
+            "The commitment must be the SHA-256 hash of the move and the salt".into(),
+        ));
+    }
+
+    verify_has_no_open_commitment(context, author)
+}
+
+/**
+ * Returns error if the author has committed to a move that they haven't revealed yet,
+ * in which case the only game move that they can make is its reveal
+ */
+pub(crate) fn verify_has_no_open_commitment(
+    context: &MoveContext,
+    author: &AgentPubKeyB64,
+) -> ExternResult<()> {
+    match context.open_commitments.contains_key(author) {
+        false => Ok(()),
+        true => Err(WasmError::Guest(
+            "The author has a commit move that they haven't revealed yet".into(),
+        )),
+    }
+}
+
+/** Private helpers */
+
+fn verify_reveal_matches_commit(
+    commit: &GameMoveEntry,
+    move_bytes: &SerializedBytes,
+    salt: &Vec<u8>,
+) -> ExternResult<()> {
+    match &commit.game_move {
+        MoveType::CommitMove { commitment } => {
+            match move_commitment(move_bytes, salt)?.eq(commitment) {
+                true => Ok(()),
+                false => Err(WasmError::Guest(
+                    "The revealed move doesn't match the commitment".into(),
+                )),
+            }
+        }
+        _ => Err(WasmError::Guest(
+            "The revealed move doesn't point to a commit move".into(),
+        )),
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
 
-use crate::{GameEntry, get_game, signal::SignalPayload};
+use crate::{get_game, signal::SignalPayload, GameEntry};
 
 pub fn get_my_current_games() -> ExternResult<BTreeMap<EntryHashB64, GameEntry>> {
     get_current_games_for(agent_info()?.agent_initial_pubkey)
@@ -29,7 +29,7 @@ pub fn remove_current_game(game_hash: EntryHashB64) -> ExternResult<()> {
     remove_my_current_game(game_hash.clone().into())?;
 
     let players: Vec<AgentPubKeyB64> = game
-        .players
+        .members()
         .into_iter()
         .filter(|p| !my_pub_key.eq(&AgentPubKey::from(p.clone())))
         .collect();
@@ -72,7 +72,7 @@ pub fn remove_my_current_game(game_hash: EntryHash) -> ExternResult<()> {
     Ok(())
 }
 
-fn remove_current_game_for_agent(
+pub(crate) fn remove_current_game_for_agent(
     game_hash: EntryHash,
     agent_pub_key: AgentPubKey,
 ) -> ExternResult<()> {
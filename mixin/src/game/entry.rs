@@ -2,12 +2,116 @@ use chrono::serde::ts_milliseconds;
 use chrono::{DateTime, Utc};
 use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 
+use crate::time_control::TimeControl;
+use crate::turn_based_game::TurnBasedGame;
+
 #[hdk_entry(id = "game_entry")]
 #[derive(Clone)]
 pub struct GameEntry {
+    // Seats of the game, in the order in which they move, each one identified by its first member
     pub players: Vec<AgentPubKeyB64>,
     #[serde(with = "ts_milliseconds")]
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub settings: GameSettings,
+    // Rest of the members of each seat in team games, who can also move for it
+    #[serde(default)]
+    pub teammates: BTreeMap<AgentPubKeyB64, Vec<AgentPubKeyB64>>,
+    // Serialized options of the game, None if it was created with the default ones
+    #[serde(default)]
+    pub options: Option<SerializedBytes>,
+    // Match that the game is part of, if any
+    #[serde(default)]
+    pub match_hash: Option<EntryHashB64>,
+    // Result of the previous game of the match, None if this is its first game,
+    // which chains the games of the match so that they can be validated
+    #[serde(default)]
+    pub previous_match_result: Option<EntryHashB64>,
+}
+
+impl GameEntry {
+    /**
+     * Seat held by the given agent, or None if they are not playing the game
+     */
+    pub fn seat_of(&self, agent: &AgentPubKeyB64) -> Option<AgentPubKeyB64> {
+        seat_of(&self.players, &self.teammates, agent)
+    }
+
+    /**
+     * All the agents playing the game, including the teammates of each seat
+     */
+    pub fn members(&self) -> Vec<AgentPubKeyB64> {
+        members(&self.players, &self.teammates)
+    }
+
+    /**
+     * Teams of the game, in the order of its seats, each one starting with the agent that identifies its seat
+     */
+    pub fn teams(&self) -> Vec<Vec<AgentPubKeyB64>> {
+        self.players
+            .iter()
+            .map(|seat| {
+                let mut team = vec![seat.clone()];
+                if let Some(seat_teammates) = self.teammates.get(seat) {
+                    team.extend(seat_teammates.iter().cloned());
+                }
+                team
+            })
+            .collect()
+    }
+
+    /**
+     * Options the game was created with
+     */
+    pub fn options<G: TurnBasedGame>(&self) -> ExternResult<G::GameOptions> {
+        match self.options.clone() {
+            None => Ok(G::GameOptions::default()),
+            Some(options_bytes) => G::GameOptions::try_from(options_bytes).or(Err(
+                WasmError::Guest("Couldn't deserialize game options".into()),
+            )),
+        }
+    }
+}
+
+/**
+ * Settings that the players agree on when creating the game
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GameSettings {
+    pub time_control: Option<TimeControl>,
+    // Time after which the rivals of the players on turn can claim their forfeit
+    pub inactivity_threshold_ms: Option<i64>,
+}
+
+/** Helper functions */
+
+pub(crate) fn seat_of(
+    players: &Vec<AgentPubKeyB64>,
+    teammates: &BTreeMap<AgentPubKeyB64, Vec<AgentPubKeyB64>>,
+    agent: &AgentPubKeyB64,
+) -> Option<AgentPubKeyB64> {
+    if players.contains(agent) {
+        return Some(agent.clone());
+    }
+
+    teammates
+        .iter()
+        .find(|(_, seat_teammates)| seat_teammates.contains(agent))
+        .map(|(seat, _)| seat.clone())
+}
+
+pub(crate) fn members(
+    players: &Vec<AgentPubKeyB64>,
+    teammates: &BTreeMap<AgentPubKeyB64, Vec<AgentPubKeyB64>>,
+) -> Vec<AgentPubKeyB64> {
+    let mut members = players.clone();
+
+    for seat_teammates in teammates.values() {
+        members.extend(seat_teammates.iter().cloned());
+    }
+
+    members
 }
@@ -2,66 +2,123 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 use hdk::prelude::holo_hash::{AgentPubKeyB64, EntryHashB64};
 use hdk::prelude::*;
 use holo_hash::HeaderHashB64;
+use std::collections::BTreeMap;
 
 use crate::current_games;
+use crate::game_index::index_game;
 use crate::{
     game_move::{self, GameMoveEntry},
     signal::{send_signal_to_players, SignalPayload},
-    turn_based_game::TurnBasedGame,
+    turn_based_game::{GameStatus, TurnBasedGame},
 };
 
-use super::GameEntry;
+use super::{build_game_state, current_game_state, game_status, GameEntry, GameSettings};
 
 /** Public handlers */
 
 /**
- * Creates the game
+ * Creates the game with the default settings
  */
 pub fn create_game(players: Vec<AgentPubKeyB64>) -> ExternResult<EntryHashB64> {
-    let now = sys_time()?.as_seconds_and_nanos();
-
-    let date_time = DateTime::from_utc(NaiveDateTime::from_timestamp(now.0, now.1), Utc);
+    create_game_with_settings(players, GameSettings::default())
+}
 
-    let game = GameEntry {
-        players: players.clone(),
-        created_at: date_time,
-    };
+/**
+ * Creates the game with the given settings
+ */
+pub fn create_game_with_settings(
+    players: Vec<AgentPubKeyB64>,
+    settings: GameSettings,
+) -> ExternResult<EntryHashB64> {
+    create_team_game(players.into_iter().map(|p| vec![p]).collect(), settings)
+}
 
-    create_entry(&game)?;
+/**
+ * Creates a game in which each seat is held by a team of agents, any of whom can move for it
+ *
+ * Seats move in the order of the teams, and each one is identified by the first member of its team
+ */
+pub fn create_team_game(
+    teams: Vec<Vec<AgentPubKeyB64>>,
+    settings: GameSettings,
+) -> ExternResult<EntryHashB64> {
+    create_game_entry(teams, settings, None, None, None)
+}
 
-    let game_hash = hash_entry(&game)?;
+/**
+ * Creates the game with the given settings and options, which configure the rules of the game
+ */
+pub fn create_game_with_options<G: TurnBasedGame>(
+    players: Vec<AgentPubKeyB64>,
+    settings: GameSettings,
+    options: G::GameOptions,
+) -> ExternResult<EntryHashB64> {
+    let options_bytes: SerializedBytes = options.try_into().or(Err(WasmError::Guest(
+        "Couldn't serialize game options".into(),
+    )))?;
+
+    create_game_entry(
+        players.into_iter().map(|p| vec![p]).collect(),
+        settings,
+        Some(options_bytes),
+        None,
+        None,
+    )
+}
 
-    current_games::add_current_game(game_hash.clone(), players)?;
+/**
+ * Gets the current state of the game
+ */
+pub fn get_game_state<G: TurnBasedGame>(game_hash: EntryHashB64) -> ExternResult<G> {
+    let last_move = game_move::handlers::get_last_move(game_hash.clone())?;
+    let game = get_game(game_hash.clone())?;
 
-    let game_hash_b64 = EntryHashB64::from(game_hash);
+    get_current_game_state::<G>(game_hash, &game, last_move.as_ref().map(|(_, m)| m))
+}
 
-    let signal = SignalPayload::GameStarted {
-        game_hash: game_hash_b64.clone(),
-        game_entry: game.clone(),
-    };
+/**
+ * Gets the current status of the game, taking into account the moves handled by the mixin
+ */
+pub fn get_game_status<G: TurnBasedGame>(game_hash: EntryHashB64) -> ExternResult<GameStatus> {
+    let last_move = game_move::handlers::get_last_move(game_hash.clone())?;
+    let game = get_game(game_hash.clone())?;
+    let last_move_entry = last_move.as_ref().map(|(_, m)| m);
 
-    send_signal_to_players(game, signal)?;
+    let game_state = get_current_game_state::<G>(game_hash, &game, last_move_entry)?;
 
-    Ok(game_hash_b64)
+    Ok(game_status(&game_state, last_move_entry))
 }
 
 /**
- * Gets the current state of the game
+ * Returns the current state of the game, read from its last move
+ *
+ * If the state stored in the last move can't be deserialized, e.g. because the game state changed its format,
+ * it's rebuilt by replaying all the moves of the game
  */
-pub fn get_game_state<G: TurnBasedGame>(game_hash: EntryHashB64) -> ExternResult<G> {
-    let moves = game_move::handlers::get_moves_entries(game_hash.clone())?;
-    let game = get_game(game_hash.clone())?;
-    let only_moves: Vec<GameMoveEntry> = moves.iter().map(|m| m.1.clone()).collect();
-
-    build_game_state::<G>(&game, &only_moves)
+pub(crate) fn get_current_game_state<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    game: &GameEntry,
+    last_move: Option<&GameMoveEntry>,
+) -> ExternResult<G> {
+    match current_game_state::<G>(game, last_move) {
+        Ok(game_state) => Ok(game_state),
+        Err(_) => {
+            let moves: Vec<GameMoveEntry> = game_move::handlers::get_moves_entries(game_hash)?
+                .into_iter()
+                .map(|(_, game_move)| game_move)
+                .collect();
+
+            build_game_state::<G>(game, &moves)
+        }
+    }
 }
 
 pub(crate) fn verify_we_see_previous_move_hash(
-    fetched_moves: &Vec<(HeaderHashB64, GameMoveEntry)>,
+    last_move: Option<&(HeaderHashB64, GameMoveEntry)>,
     previous_move_hash: Option<HeaderHashB64>,
 ) -> ExternResult<()> {
     // If we can't see the previous move hash yet, return error for the UI to retry
-    match (fetched_moves.last(), previous_move_hash) {
+    match (last_move, previous_move_hash) {
         (None, None) => Ok(()),
         (Some((fetched_header_hash, _)), Some(required_header_hash))
             if fetched_header_hash.eq(&required_header_hash) =>
@@ -84,24 +141,62 @@ pub fn get_game(game_hash: EntryHashB64) -> ExternResult<GameEntry> {
         .ok_or(WasmError::Guest("Couldn't deserialize game entry".into()))
 }
 
-pub(crate) fn build_game_state<G: TurnBasedGame>(
-    game_entry: &GameEntry,
-    moves: &Vec<GameMoveEntry>,
-) -> ExternResult<G> {
-    let mut game_state = G::initial(game_entry.players.clone());
+/**
+ * Creates the game entry for the given teams, links it as a current game of all its members and notifies them
+ *
+ * Games of a match point to the match and to the result of its previous game
+ */
+pub(crate) fn create_game_entry(
+    teams: Vec<Vec<AgentPubKeyB64>>,
+    settings: GameSettings,
+    options: Option<SerializedBytes>,
+    match_hash: Option<EntryHashB64>,
+    previous_match_result: Option<EntryHashB64>,
+) -> ExternResult<EntryHashB64> {
+    let now = sys_time()?.as_seconds_and_nanos();
 
-    for (_index, game_move) in moves.iter().enumerate() {
-        game_state = apply_move(game_state, game_move)?;
+    let date_time = DateTime::from_utc(NaiveDateTime::from_timestamp(now.0, now.1), Utc);
+
+    let mut players = vec![];
+    let mut teammates = BTreeMap::new();
+
+    for team in teams {
+        let (seat, seat_teammates) = team
+            .split_first()
+            .ok_or(WasmError::Guest("Teams can't be empty".into()))?;
+
+        players.push(seat.clone());
+        if !seat_teammates.is_empty() {
+            teammates.insert(seat.clone(), seat_teammates.to_vec());
+        }
     }
-    return Ok(game_state);
-}
 
-pub(crate) fn apply_move<G: TurnBasedGame>(
-    game_state: G,
-    game_move: &GameMoveEntry,
-) -> ExternResult<G> {
-    let move_content = G::GameMove::try_from(game_move.game_move.clone())
-        .or(Err(WasmError::Guest("Couldnt't convert game move".into())))?;
+    let game = GameEntry {
+        players,
+        created_at: date_time,
+        settings,
+        teammates,
+        options,
+        match_hash,
+        previous_match_result,
+    };
+
+    create_entry(&game)?;
+
+    let game_hash = hash_entry(&game)?;
+
+    current_games::add_current_game(game_hash.clone(), game.members())?;
+
+    let game_hash_b64 = EntryHashB64::from(game_hash);
+
+    index_game(game_hash_b64.clone(), &game)?;
 
-    game_state.apply_move(move_content, game_move.author_pub_key.clone())
+    let signal = SignalPayload::GameStarted {
+        game_hash: game_hash_b64.clone(),
+        game_entry: game.clone(),
+    };
+
+    send_signal_to_players(game, signal)?;
+
+    Ok(game_hash_b64)
 }
@@ -1,7 +1,9 @@
 mod entry;
 mod handlers;
-//mod validation;
+mod state;
+mod validation;
 
 pub use entry::*;
 pub use handlers::*;
-//pub use validation::*;
\ No newline at end of file
+pub use state::*;
+pub use validation::*;
@@ -0,0 +1,184 @@
+use hdk::prelude::holo_hash::AgentPubKeyB64;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::move_context::MoveContext;
+use crate::takeback::effective_moves;
+use crate::{
+    game_move::{GameMoveEntry, MoveType},
+    turn_based_game::{GameStatus, TurnBasedGame},
+};
+
+use super::GameEntry;
+
+/** Helper functions */
+
+/**
+ * Builds the state of the game by replaying all its effective moves from its initial state
+ *
+ * Like the rest of functions in this file, it doesn't call any host function,
+ * so it can also run outside of a conductor, as the `GameSimulator` does
+ */
+pub(crate) fn build_game_state<G: TurnBasedGame>(
+    game_entry: &GameEntry,
+    moves: &Vec<GameMoveEntry>,
+) -> ExternResult<G> {
+    let mut game_state = G::initial(game_entry.players.clone(), game_entry.options::<G>()?);
+    let mut context = MoveContext::default();
+
+    // Moves reverted by a takeback don't count for the state of the game
+    for game_move in effective_moves(moves).iter() {
+        game_state = apply_move(game_state, game_move, &game_entry.players, &context)?;
+        context.observe(&game_entry.players, game_move)?;
+    }
+    return Ok(game_state);
+}
+
+/**
+ * Returns the current state of the game, given its last move
+ *
+ * Every move stores the state that results from it, which is validated against the previous move,
+ * so the state is read from the last move instead of replaying the whole game
+ */
+pub(crate) fn current_game_state<G: TurnBasedGame>(
+    game_entry: &GameEntry,
+    last_move: Option<&GameMoveEntry>,
+) -> ExternResult<G> {
+    match last_move {
+        None => build_game_state::<G>(game_entry, &vec![]),
+        Some(last_move) => G::try_from(last_move.resulting_game_state.clone()).or(Err(
+            WasmError::Guest("Couldn't deserialize game state".into()),
+        )),
+    }
+}
+
+pub(crate) fn apply_move<G: TurnBasedGame>(
+    game_state: G,
+    game_move: &GameMoveEntry,
+    players: &Vec<AgentPubKeyB64>,
+    context: &MoveContext,
+) -> ExternResult<G> {
+    apply_move_type(
+        game_state,
+        &game_move.game_move,
+        &game_move.author_pub_key,
+        players,
+        context,
+    )
+}
+
+/**
+ * Applies the move to the game state
+ *
+ * Moves that are handled by the mixin itself leave the game state untouched,
+ * except for the reveal that completes a simultaneous round, which resolves it
+ */
+pub(crate) fn apply_move_type<G: TurnBasedGame>(
+    game_state: G,
+    move_type: &MoveType,
+    author: &AgentPubKeyB64,
+    players: &Vec<AgentPubKeyB64>,
+    context: &MoveContext,
+) -> ExternResult<G> {
+    match move_type {
+        // The game only receives the revealed data of the moves that were committed to
+        MoveType::Game(move_bytes)
+        | MoveType::RevealMove {
+            game_move: move_bytes,
+            ..
+        } => {
+            let move_content = G::GameMove::try_from(move_bytes.clone())
+                .or(Err(WasmError::Guest("Couldnt't convert game move".into())))?;
+
+            game_state.apply_move_with_rng(
+                move_content,
+                author.clone(),
+                context.random_beacon.rng(),
+            )
+        }
+        MoveType::RevealRoundMove {
+            game_move: move_bytes,
+            ..
+        } => match context.round.completed_by(players, author, move_bytes) {
+            None => Ok(game_state),
+            Some(round_moves) => {
+                let mut moves = BTreeMap::new();
+
+                for (player, move_bytes) in round_moves {
+                    let move_content = G::GameMove::try_from(move_bytes)
+                        .or(Err(WasmError::Guest("Couldnt't convert game move".into())))?;
+                    moves.insert(player, move_content);
+                }
+
+                game_state.apply_round(moves)
+            }
+        },
+        _ => Ok(game_state),
+    }
+}
+
+/**
+ * Returns the status of the game, given its state and its last move
+ *
+ * Moves handled by the mixin, like resigning or accepting a draw, can finish the game regardless of its state
+ */
+pub(crate) fn game_status<G: TurnBasedGame>(
+    game_state: &G,
+    last_move: Option<&GameMoveEntry>,
+) -> GameStatus {
+    match last_move {
+        Some(last_move) if last_move.game_move.finishes_game() => GameStatus::Finished,
+        _ => game_state.status(),
+    }
+}
+
+/**
+ * Returns whether no more moves can be made in the game, given its state and its last move
+ */
+pub(crate) fn is_finished<G: TurnBasedGame>(
+    game_state: &G,
+    last_move: Option<&GameMoveEntry>,
+) -> bool {
+    match game_status(game_state, last_move) {
+        GameStatus::Finished => true,
+        GameStatus::Ongoing => false,
+    }
+}
+
+/**
+ * Returns the players whose time is running and who are blamed for the inactivity of the game, given the context of the next move
+ *
+ * In games played in rounds, these are the players that the current round is waiting for, and in the rest,
+ * the players allowed to make the next move
+ */
+pub(crate) fn players_on_clock<G: TurnBasedGame>(
+    game_state: &G,
+    game_entry: &GameEntry,
+    context: &MoveContext,
+) -> Vec<AgentPubKeyB64> {
+    match G::plays_in_rounds() {
+        true => context.round.pending_players(&game_entry.players),
+        false => game_state.allowed_movers(&game_entry.players, context.last_game_mover.as_ref()),
+    }
+}
+
+/**
+ * Returns error if the author is not one of the players allowed to make the next move,
+ * given the author of the last move defined by the game
+ */
+pub(crate) fn verify_it_is_authors_turn<G: TurnBasedGame>(
+    game_state: &G,
+    game_entry: &GameEntry,
+    last_mover: Option<&AgentPubKeyB64>,
+    author: &AgentPubKeyB64,
+) -> ExternResult<()> {
+    match game_state
+        .allowed_movers(&game_entry.players, last_mover)
+        .contains(author)
+    {
+        true => Ok(()),
+        false => Err(WasmError::Guest(
+            "It's not the turn of the author of the move".into(),
+        )),
+    }
+}
@@ -1,58 +1,213 @@
-/**use std::collections::HashMap;
+use std::collections::HashMap;
 
 use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
 
 use super::GameEntry;
+use crate::game_match::{match_game_teams, match_state, MatchEntry};
+use crate::game_result::GameResultEntry;
+use crate::time_control::timestamp_to_date_time;
+use crate::turn_based_game::GameStatus;
 use crate::TurnBasedGame;
 
+// The creation date of the game is taken from the clock of its creator just before committing it
+const MAX_CREATED_AT_DRIFT_MS: i64 = 5_000;
+
 /**
  * Validates the game, returning error if:
  *
- * - There is a repeated player in the game
+ * - There is a repeated player in the game, counting the teammates of each seat
  * - The number of players is within the bounds defined by the game
+ * - There are teammates for a seat that is not in the game
+ * - The options of the game are not valid for the game with its players
+ * - The game is part of a match that is not played by the same teams with the same settings and options,
+ *   that already has all its games or that has already finished
+ * - The time control or the inactivity threshold of the game have negative times
+ * - Its creation date is not the moment in which it was committed, as the clocks and the inactivity of the game
+ *   start counting from it
  */
 pub fn validate_game_entry<G: TurnBasedGame>(
-    data: ValidateData,
+    timestamp: Timestamp,
+    game: GameEntry,
 ) -> ExternResult<ValidateCallbackResult> {
-    let maybe_game: Option<GameEntry> = data.element.entry().to_app_option()?;
-
-    if let Some(game) = maybe_game {
-        let mut players_map: HashMap<AgentPubKeyB64, bool> = HashMap::new();
-
-        for player in game.players.iter() {
-            if players_map.contains_key(player) {
-                return Ok(ValidateCallbackResult::Invalid(format!(
-                    "Game contains a repeated agent: {}",
-                    player
-                )));
-            }
-            players_map.insert(player.clone(), true);
+    let committed_at = timestamp_to_date_time(timestamp);
+    let created_at_drift_ms = (committed_at - game.created_at).num_milliseconds().abs();
+
+    if created_at_drift_ms > MAX_CREATED_AT_DRIFT_MS {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The creation date of the game is not the moment in which it was committed".into(),
+        ));
+    }
+
+    if let Some(time_control) = game.settings.time_control.clone() {
+        let negative_time = time_control.move_time_limit_ms.unwrap_or(0) < 0
+            || time_control.time_bank_ms.unwrap_or(0) < 0
+            || time_control.increment_ms < 0;
+
+        if negative_time {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The time control of the game can't have negative times".into(),
+            ));
         }
-        if let Some(min_players) = G::min_players() {
-            if game.players.len() < min_players {
-                return Ok(ValidateCallbackResult::Invalid(String::from(
-                    "Bad number of players",
-                )));
-            }
+    }
+
+    if game.settings.inactivity_threshold_ms.unwrap_or(0) < 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The inactivity threshold of the game can't be negative".into(),
+        ));
+    }
+
+    if let Some(seat) = game.teammates.keys().find(|s| !game.players.contains(s)) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "There are teammates for a seat that is not in the game: {}",
+            seat
+        )));
+    }
+
+    if let ValidateCallbackResult::Invalid(error) = validate_players::<G>(&game.players)? {
+        return Ok(ValidateCallbackResult::Invalid(error));
+    }
+
+    if let ValidateCallbackResult::Invalid(error) = validate_no_repeated_agents(&game.members())? {
+        return Ok(ValidateCallbackResult::Invalid(error));
+    }
+
+    let options = match game.options::<G>() {
+        Ok(options) => options,
+        Err(_) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Couldn't deserialize the options of the game".into(),
+            ))
         }
-        if let Some(max_players) = G::max_players() {
-            if game.players.len() > max_players {
-                return Ok(ValidateCallbackResult::Invalid(String::from(
-                    "Bad number of players",
-                )));
-            }
+    };
+
+    if let Err(error) = G::validate_options(&options, &game.players) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid game options: {:?}",
+            error
+        )));
+    }
+
+    if let Some(match_hash) = game.match_hash.clone() {
+        return validate_match_game(&game, match_hash);
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/**
+ * Validates that the given players can play a game together
+ */
+pub fn validate_players<G: TurnBasedGame>(
+    players: &Vec<AgentPubKeyB64>,
+) -> ExternResult<ValidateCallbackResult> {
+    if let ValidateCallbackResult::Invalid(error) = validate_no_repeated_agents(players)? {
+        return Ok(ValidateCallbackResult::Invalid(error));
+    }
+    if let Some(min_players) = G::min_players() {
+        if players.len() < min_players {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Bad number of players",
+            )));
         }
-    } else {
-        return match data.element.header().entry_hash() {
-            None => Ok(ValidateCallbackResult::Invalid(
-                "Bad create game element: no entry inside".into(),
-            )),
-            Some(game_entry_hash) => Ok(ValidateCallbackResult::UnresolvedDependencies(vec![
-                game_entry_hash.clone().into(),
-            ])),
-        };
     }
+    if let Some(max_players) = G::max_players() {
+        if players.len() > max_players {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "Bad number of players",
+            )));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/** Private helpers */
+
+/**
+ * Validates the game against its match, walking back the results of the previous games of the match,
+ * which are at most best_of
+ */
+fn validate_match_game(
+    game: &GameEntry,
+    match_hash: EntryHashB64,
+) -> ExternResult<ValidateCallbackResult> {
+    let match_entry: MatchEntry = must_get_entry(match_hash.clone().into())?
+        .as_content()
+        .try_into()?;
+
+    let mut previous_games = vec![];
+    let mut previous_outcomes = vec![];
+    let mut maybe_previous_result = game.previous_match_result.clone();
+
+    while let Some(previous_result_hash) = maybe_previous_result {
+        if previous_games.len() as u32 >= match_entry.best_of {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The match already has all its games".into(),
+            ));
+        }
+
+        let previous_result: GameResultEntry = must_get_entry(previous_result_hash.into())?
+            .as_content()
+            .try_into()?;
+        let previous_game: GameEntry = must_get_entry(previous_result.game_hash.clone().into())?
+            .as_content()
+            .try_into()?;
+
+        if previous_game.match_hash != Some(match_hash.clone()) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The previous game is not a game of this match".into(),
+            ));
+        }
+
+        previous_games.push(previous_result.game_hash);
+        previous_outcomes.push(Some(previous_result.outcome));
+        maybe_previous_result = previous_game.previous_match_result;
+    }
+
+    if previous_games.len() as u32 >= match_entry.best_of {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The match already has all its games".into(),
+        ));
+    }
+
+    if !game
+        .teams()
+        .eq(&match_game_teams(&match_entry, previous_games.len()))
+        || !game.settings.eq(&match_entry.settings)
+        || !game.options.eq(&match_entry.options)
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The game is not played by the teams of its match with the same settings and options"
+                .into(),
+        ));
+    }
+
+    let state = match_state(match_entry, previous_games, previous_outcomes);
+
+    if let GameStatus::Finished = state.status {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The match has already finished".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_no_repeated_agents(
+    agents: &Vec<AgentPubKeyB64>,
+) -> ExternResult<ValidateCallbackResult> {
+    let mut agents_map: HashMap<AgentPubKeyB64, bool> = HashMap::new();
+
+    for agent in agents.iter() {
+        if agents_map.contains_key(agent) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Game contains a repeated agent: {}",
+                agent
+            )));
+        }
+        agents_map.insert(agent.clone(), true);
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
-**/
\ No newline at end of file
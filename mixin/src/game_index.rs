@@ -0,0 +1,327 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{game::GameEntry, time_control::now, turn_based_game::GameStatus};
+
+/**
+ * Time range of the games to list, by their creation time
+ */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameIndexQuery {
+    // Only games created strictly before this moment, now if None
+    // To get the next page, pass the `next_before` of the previous page
+    pub before: Option<DateTime<Utc>>,
+    // Also include the games created exactly at `before` that are listed after this game,
+    // to get the next page pass the `next_before_game` of the previous page
+    #[serde(default)]
+    pub before_game: Option<EntryHashB64>,
+    // Only games created at or after this moment
+    pub since: DateTime<Utc>,
+    // Maximum number of games to return
+    pub limit: Option<usize>,
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IndexedGame {
+    pub game_hash: EntryHashB64,
+    pub game: GameEntry,
+    pub status: GameStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IndexedGamesPage {
+    pub games: Vec<IndexedGame>,
+    // Value of `before` to get the next page, None if there are no more games in the time range
+    pub next_before: Option<DateTime<Utc>>,
+    // Value of `before_game` to get the next page, so that games created in the same millisecond are not skipped
+    pub next_before_game: Option<EntryHashB64>,
+}
+
+/**
+ * Maximum number of buckets, days and hours, that are looked up in a single call
+ */
+const MAX_BUCKETS_PER_CALL: usize = 200;
+
+/** Public handlers */
+
+/**
+ * Gets all the games created in the given time range, from the most recent to the oldest one
+ */
+pub fn get_recent_games(query: GameIndexQuery) -> ExternResult<IndexedGamesPage> {
+    get_indexed_games(query, vec![GameStatus::Ongoing, GameStatus::Finished])
+}
+
+/**
+ * Gets the games created in the given time range that have not finished yet, from the most recent to the oldest one
+ */
+pub fn get_ongoing_games(query: GameIndexQuery) -> ExternResult<IndexedGamesPage> {
+    get_indexed_games(query, vec![GameStatus::Ongoing])
+}
+
+/**
+ * Gets the games created in the given time range that have already finished, from the most recent to the oldest one
+ */
+pub fn get_finished_games(query: GameIndexQuery) -> ExternResult<IndexedGamesPage> {
+    get_indexed_games(query, vec![GameStatus::Finished])
+}
+
+/** Helper functions */
+
+/**
+ * Adds the newly created game to the index of ongoing games
+ */
+pub(crate) fn index_game(game_hash: EntryHashB64, game: &GameEntry) -> ExternResult<()> {
+    let path = bucket_path(game.created_at);
+    path.ensure()?;
+
+    create_link(
+        path.hash()?,
+        EntryHash::from(game_hash),
+        LinkType(0),
+        status_tag(&GameStatus::Ongoing),
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Moves the game from the index of ongoing games to the index of finished games
+ */
+pub(crate) fn index_finished_game(game_hash: EntryHashB64, game: &GameEntry) -> ExternResult<()> {
+    let path = bucket_path(game.created_at);
+    let game_hash = EntryHash::from(game_hash);
+
+    for link in get_links(path.hash()?, Some(status_tag(&GameStatus::Ongoing)))?
+        .into_iter()
+        .filter(|link| link.target.eq(&game_hash))
+    {
+        delete_link(link.create_link_hash)?;
+    }
+
+    path.ensure()?;
+
+    create_link(
+        path.hash()?,
+        game_hash,
+        LinkType(0),
+        status_tag(&GameStatus::Finished),
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Removes the game from the index, whatever its status
+ */
+pub(crate) fn unindex_game(game_hash: EntryHashB64, game: &GameEntry) -> ExternResult<()> {
+    let path = bucket_path(game.created_at);
+    let game_hash = EntryHash::from(game_hash);
+
+    for status in [GameStatus::Ongoing, GameStatus::Finished].iter() {
+        for link in get_links(path.hash()?, Some(status_tag(status)))?
+            .into_iter()
+            .filter(|link| link.target.eq(&game_hash))
+        {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+/** Private helpers */
+
+/**
+ * Walks the daily buckets from the end of the time range to its start, and the hourly buckets of the days
+ * that have any games, until it has enough games or it has looked up too many buckets
+ *
+ * Games are listed by their creation time and then by their hash, so that the games of a page can be
+ * continued from the last one even if several games were created in the same millisecond
+ */
+fn get_indexed_games(
+    query: GameIndexQuery,
+    statuses: Vec<GameStatus>,
+) -> ExternResult<IndexedGamesPage> {
+    let before = match query.before {
+        Some(before) => before,
+        None => now()? + Duration::milliseconds(1),
+    };
+    // Games created at `before` are also in range when the page continues from one of them
+    let before = match query.before_game {
+        Some(_) => before + Duration::milliseconds(1),
+        None => before,
+    };
+    let limit = query.limit.unwrap_or(usize::MAX);
+
+    let mut games: Vec<IndexedGame> = vec![];
+    let mut visited_buckets = 0;
+    let mut day_start = bucket_start(before, Duration::days(1));
+
+    while day_start + Duration::days(1) > query.since && games.len() < limit {
+        if visited_buckets >= MAX_BUCKETS_PER_CALL {
+            return Ok(IndexedGamesPage {
+                games,
+                next_before: Some(day_start + Duration::days(1)),
+                next_before_game: None,
+            });
+        }
+        visited_buckets += 1;
+
+        if !day_path(day_start).children()?.is_empty() {
+            let mut hour_start = bucket_start(
+                before.min(day_start + Duration::days(1)) - Duration::milliseconds(1),
+                Duration::hours(1),
+            );
+
+            while hour_start >= day_start
+                && hour_start + Duration::hours(1) > query.since
+                && games.len() < limit
+            {
+                if visited_buckets >= MAX_BUCKETS_PER_CALL {
+                    return Ok(IndexedGamesPage {
+                        games,
+                        next_before: Some(hour_start + Duration::hours(1)),
+                        next_before_game: None,
+                    });
+                }
+                visited_buckets += 1;
+
+                let mut bucket_games = get_bucket_games(hour_start, &statuses, &query, before)?;
+                games.append(&mut bucket_games);
+
+                hour_start = hour_start - Duration::hours(1);
+            }
+        }
+
+        day_start = day_start - Duration::days(1);
+    }
+
+    // There can be more games before the last one of the page
+    let (next_before, next_before_game) = match limit > 0 && games.len() >= limit {
+        true => (
+            Some(games[limit - 1].game.created_at),
+            Some(games[limit - 1].game_hash.clone()),
+        ),
+        false => (None, None),
+    };
+
+    Ok(IndexedGamesPage {
+        games: games.into_iter().take(limit).collect(),
+        next_before,
+        next_before_game,
+    })
+}
+
+/**
+ * Gets the games with the given statuses in the hourly bucket that starts at the given moment and are in the time range,
+ * from the most recent to the oldest one
+ */
+fn get_bucket_games(
+    hour_start: DateTime<Utc>,
+    statuses: &Vec<GameStatus>,
+    query: &GameIndexQuery,
+    before: DateTime<Utc>,
+) -> ExternResult<Vec<IndexedGame>> {
+    let path = bucket_path(hour_start);
+    let mut bucket_games = vec![];
+
+    for status in statuses.iter() {
+        let links = get_links(path.hash()?, Some(status_tag(status)))?;
+
+        let get_inputs = links
+            .into_iter()
+            .map(|l| GetInput::new(l.target.into(), GetOptions::default()))
+            .collect();
+
+        let elements = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+        for element in elements.into_iter().filter_map(|m| m) {
+            let game: GameEntry = element
+                .entry()
+                .to_app_option()?
+                .ok_or(WasmError::Guest("Could not convert game entry".into()))?;
+
+            let game_hash = element
+                .header()
+                .entry_hash()
+                .ok_or(WasmError::Guest("Bad create game header".into()))?;
+
+            if game.created_at < before
+                && game.created_at >= query.since
+                && is_after_cursor(&game, game_hash, query)
+            {
+                bucket_games.push(IndexedGame {
+                    game_hash: game_hash.clone().into(),
+                    status: status.clone(),
+                    game,
+                });
+            }
+        }
+    }
+
+    bucket_games.sort_by(|a, b| {
+        b.game.created_at.cmp(&a.game.created_at).then_with(|| {
+            EntryHash::from(b.game_hash.clone())
+                .get_raw_39()
+                .cmp(EntryHash::from(a.game_hash.clone()).get_raw_39())
+        })
+    });
+
+    Ok(bucket_games)
+}
+
+/**
+ * Whether the game is listed after the game of the cursor of the query, when the page continues from a game
+ * that was created in the same millisecond
+ */
+fn is_after_cursor(game: &GameEntry, game_hash: &EntryHash, query: &GameIndexQuery) -> bool {
+    match (query.before, query.before_game.as_ref()) {
+        (Some(before), Some(before_game)) if game.created_at.eq(&before) => {
+            game_hash.get_raw_39() < EntryHash::from(before_game.clone()).get_raw_39()
+        }
+        (Some(before), _) => game.created_at < before,
+        (None, _) => true,
+    }
+}
+
+/**
+ * Start of the bucket of the given duration that contains the given moment
+ */
+fn bucket_start(date_time: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    let timestamp = date_time.timestamp();
+
+    DateTime::from_utc(
+        NaiveDateTime::from_timestamp(timestamp - timestamp.rem_euclid(duration.num_seconds()), 0),
+        Utc,
+    )
+}
+
+/**
+ * Path that groups the hourly buckets of the day of the given moment, which only has children if any game was created that day
+ */
+fn day_path(date_time: DateTime<Utc>) -> Path {
+    Path::from(vec![
+        Component::from(String::from("all_games")),
+        Component::from(date_time.format("%Y-%m-%d").to_string()),
+    ])
+}
+
+/**
+ * Path in which all the games created in the same hour as the given moment are anchored, inside the path of its day
+ */
+fn bucket_path(date_time: DateTime<Utc>) -> Path {
+    Path::from(vec![
+        Component::from(String::from("all_games")),
+        Component::from(date_time.format("%Y-%m-%d").to_string()),
+        Component::from(date_time.format("%H").to_string()),
+    ])
+}
+
+fn status_tag(status: &GameStatus) -> LinkTag {
+    match status {
+        GameStatus::Ongoing => LinkTag::new("ongoing_games"),
+        GameStatus::Finished => LinkTag::new("finished_games"),
+    }
+}
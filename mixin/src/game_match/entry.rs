@@ -0,0 +1,59 @@
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use crate::game::GameSettings;
+use crate::turn_based_game::GameStatus;
+
+/**
+ * Series of games played by the same teams, won by the seat with the highest cumulative score
+ */
+#[hdk_entry(id = "match_entry")]
+#[derive(Clone)]
+pub struct MatchEntry {
+    // Teams of the match, in the order of the seats of its first game
+    pub teams: Vec<Vec<AgentPubKeyB64>>,
+    // Maximum number of games of the match, which finishes earlier if a seat can't be caught up
+    pub best_of: u32,
+    pub settings: GameSettings,
+    // Serialized options of all the games of the match, None for the default ones
+    pub options: Option<SerializedBytes>,
+    #[serde(with = "ts_milliseconds")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl MatchEntry {
+    /**
+     * Seats of the match, each one identified by the first member of its team
+     */
+    pub fn seats(&self) -> Vec<AgentPubKeyB64> {
+        self.teams
+            .iter()
+            .filter_map(|team| team.first())
+            .cloned()
+            .collect()
+    }
+
+    /**
+     * All the agents playing the match, including the teammates of each seat
+     */
+    pub fn members(&self) -> Vec<AgentPubKeyB64> {
+        self.teams.iter().flatten().cloned().collect()
+    }
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MatchState {
+    pub match_entry: MatchEntry,
+    // Games of the match, in the order in which they were played
+    pub games: Vec<EntryHashB64>,
+    // Sum of the scores of each seat in the finished games of the match
+    pub scores: BTreeMap<AgentPubKeyB64, f64>,
+    pub status: GameStatus,
+    // Seats with the highest score, only present once the match has finished
+    pub winners: Vec<AgentPubKeyB64>,
+}
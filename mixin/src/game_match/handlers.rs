@@ -0,0 +1,277 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::{
+    game::{create_game_entry, GameSettings},
+    game_result::get_game_result_entry,
+    signal::{send_signal_to_agents, SignalPayload},
+    turn_based_game::{GameOutcome, GameStatus, TurnBasedGame},
+};
+
+use super::{MatchEntry, MatchState};
+
+/** Public handlers */
+
+/**
+ * Creates a match of at most best_of games between the given teams, and starts its first game
+ *
+ * Every game of the match is played with the given settings and options,
+ * and the seats rotate in each game so that every team starts the same number of times
+ */
+pub fn create_match<G: TurnBasedGame>(
+    teams: Vec<Vec<AgentPubKeyB64>>,
+    best_of: u32,
+    settings: GameSettings,
+    options: G::GameOptions,
+) -> ExternResult<EntryHashB64> {
+    let now = sys_time()?.as_seconds_and_nanos();
+
+    let date_time = DateTime::from_utc(NaiveDateTime::from_timestamp(now.0, now.1), Utc);
+
+    let options_bytes: SerializedBytes = options.try_into().or(Err(WasmError::Guest(
+        "Couldn't serialize game options".into(),
+    )))?;
+
+    let match_entry = MatchEntry {
+        teams,
+        best_of,
+        settings,
+        options: Some(options_bytes),
+        created_at: date_time,
+    };
+
+    create_entry(&match_entry)?;
+
+    let match_hash: EntryHashB64 = hash_entry(&match_entry)?.into();
+
+    start_match_game(match_hash.clone(), &match_entry, 0, None)?;
+
+    Ok(match_hash)
+}
+
+/**
+ * Gets the match, with its games, the cumulative score of each seat and whether it has finished
+ *
+ * If all the games of the ongoing match have finished, because the player that finished the last one
+ * couldn't start the next one, and the caller is a member of the match, starts the next game
+ */
+pub fn get_match_state(match_hash: EntryHashB64) -> ExternResult<MatchState> {
+    let match_entry = get_match(match_hash.clone())?;
+    let games = get_match_games(match_hash.clone())?;
+
+    let mut results = vec![];
+    for game_hash in games.iter() {
+        results.push(get_game_result_entry(game_hash.clone())?);
+    }
+
+    let outcomes = results
+        .iter()
+        .map(|result| result.as_ref().map(|result| result.outcome.clone()))
+        .collect();
+
+    let mut state = match_state(match_entry.clone(), games, outcomes);
+
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if let (GameStatus::Ongoing, Some(Some(last_result))) = (&state.status, results.last()) {
+        if results.iter().all(|result| result.is_some())
+            && match_entry.members().contains(&my_pub_key)
+        {
+            let game_hash = start_match_game(
+                match_hash,
+                &match_entry,
+                state.games.len(),
+                Some(hash_entry(last_result)?.into()),
+            )?;
+            state.games.push(game_hash);
+        }
+    }
+
+    Ok(state)
+}
+
+pub fn get_match(match_hash: EntryHashB64) -> ExternResult<MatchEntry> {
+    let element = get(EntryHash::from(match_hash), GetOptions::default())?
+        .ok_or(WasmError::Guest("There is no match at this hash".into()))?;
+
+    element
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("Couldn't deserialize match entry".into()))
+}
+
+/** Helper functions */
+
+/**
+ * Starts the next game of the match after one of its games has finished with the given outcome and result,
+ * unless the match has finished with it
+ *
+ * If the game is not linked from the match yet, it only logs it instead of failing the move that finished the game
+ */
+pub(crate) fn continue_match(
+    match_hash: EntryHashB64,
+    finished_game_hash: EntryHashB64,
+    finished_game_result_hash: EntryHashB64,
+    outcome: GameOutcome,
+) -> ExternResult<()> {
+    let match_entry = get_match(match_hash.clone())?;
+    let games = get_match_games(match_hash.clone())?;
+
+    // The link from the match to the game may not be visible yet, in which case the next game is started
+    // by the first member of the match that gets its state once all its games have finished
+    if !games.contains(&finished_game_hash) {
+        warn!(
+            "The finished game {:?} is not linked from its match {:?} yet, not starting the next game",
+            finished_game_hash, match_hash
+        );
+        return Ok(());
+    }
+
+    // The result of the game that has just finished may not be visible yet
+    let mut outcomes = vec![];
+    for game_hash in games.iter() {
+        outcomes.push(match game_hash.eq(&finished_game_hash) {
+            true => Some(outcome.clone()),
+            false => get_game_result_entry(game_hash.clone())?.map(|result| result.outcome),
+        });
+    }
+
+    let state = match_state(match_entry.clone(), games, outcomes);
+
+    if let GameStatus::Ongoing = state.status {
+        start_match_game(
+            match_hash,
+            &match_entry,
+            state.games.len(),
+            Some(finished_game_result_hash),
+        )?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Computes the state of the match given the outcomes of its games, None for the games that haven't finished
+ *
+ * The match finishes when all its games have been played, or when the leader can't be caught up
+ * in the remaining games, given that each game gives at most one point to each seat
+ */
+pub(crate) fn match_state(
+    match_entry: MatchEntry,
+    games: Vec<EntryHashB64>,
+    outcomes: Vec<Option<GameOutcome>>,
+) -> MatchState {
+    let mut scores: BTreeMap<AgentPubKeyB64, f64> = match_entry
+        .seats()
+        .into_iter()
+        .map(|seat| (seat, 0.0))
+        .collect();
+
+    for outcome in outcomes.iter().flatten() {
+        for (seat, score) in outcome.scores.iter() {
+            if let Some(seat_score) = scores.get_mut(seat) {
+                *seat_score += score;
+            }
+        }
+    }
+
+    let finished_games = outcomes.iter().filter(|o| o.is_some()).count() as u32;
+    let remaining_games = match_entry.best_of.saturating_sub(finished_games) as f64;
+    let all_games_finished = finished_games as usize == games.len();
+
+    let max_score = scores.values().cloned().fold(0.0, f64::max);
+    let leaders: Vec<AgentPubKeyB64> = scores
+        .iter()
+        .filter(|(_, score)| **score == max_score)
+        .map(|(seat, _)| seat.clone())
+        .collect();
+
+    let leader_is_unreachable = leaders.len() == 1
+        && scores
+            .iter()
+            .filter(|(seat, _)| !leaders.contains(seat))
+            .all(|(_, score)| score + remaining_games < max_score);
+
+    let status = match all_games_finished && (remaining_games == 0.0 || leader_is_unreachable) {
+        true => GameStatus::Finished,
+        false => GameStatus::Ongoing,
+    };
+
+    let winners = match status {
+        GameStatus::Finished => leaders,
+        GameStatus::Ongoing => vec![],
+    };
+
+    MatchState {
+        match_entry,
+        games,
+        scores,
+        status,
+        winners,
+    }
+}
+
+/**
+ * Teams of the game with the given index in the match, rotating the seats so that a different team starts each game
+ */
+pub(crate) fn match_game_teams(
+    match_entry: &MatchEntry,
+    game_index: usize,
+) -> Vec<Vec<AgentPubKeyB64>> {
+    let mut teams = match_entry.teams.clone();
+    if !teams.is_empty() {
+        let rotation = game_index % teams.len();
+        teams.rotate_left(rotation);
+    }
+    teams
+}
+
+/** Private helpers */
+
+/**
+ * Creates the game with the given index in the match, after the game with the given result
+ */
+fn start_match_game(
+    match_hash: EntryHashB64,
+    match_entry: &MatchEntry,
+    game_index: usize,
+    previous_result_hash: Option<EntryHashB64>,
+) -> ExternResult<EntryHashB64> {
+    let game_hash = create_game_entry(
+        match_game_teams(match_entry, game_index),
+        match_entry.settings.clone(),
+        match_entry.options.clone(),
+        Some(match_hash.clone()),
+        previous_result_hash,
+    )?;
+
+    create_link(
+        EntryHash::from(match_hash.clone()),
+        EntryHash::from(game_hash.clone()),
+        LinkType(0),
+        match_games_tag(),
+    )?;
+
+    let signal = SignalPayload::MatchGameStarted {
+        match_hash,
+        game_hash: game_hash.clone(),
+        game_number: game_index as u32 + 1,
+    };
+
+    send_signal_to_agents(match_entry.members(), signal)?;
+
+    Ok(game_hash)
+}
+
+fn get_match_games(match_hash: EntryHashB64) -> ExternResult<Vec<EntryHashB64>> {
+    let mut links = get_links(EntryHash::from(match_hash), Some(match_games_tag()))?;
+    links.sort_by_key(|link| link.timestamp);
+
+    Ok(links.into_iter().map(|link| link.target.into()).collect())
+}
+
+fn match_games_tag() -> LinkTag {
+    LinkTag::new("match_games")
+}
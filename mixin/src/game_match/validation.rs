@@ -0,0 +1,65 @@
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use super::MatchEntry;
+use crate::game::validate_players;
+use crate::turn_based_game::TurnBasedGame;
+
+/**
+ * Validates the match, returning error if:
+ *
+ * - The match has no games
+ * - Any of its teams is empty
+ * - There is a repeated agent in the match, counting the teammates of each seat
+ * - The seats of the match can't play a game together
+ * - The options of the match are not valid for the game with its players
+ */
+pub fn validate_match_entry<G: TurnBasedGame>(
+    match_entry: MatchEntry,
+) -> ExternResult<ValidateCallbackResult> {
+    if match_entry.best_of == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A match must have at least one game".into(),
+        ));
+    }
+
+    if match_entry.teams.iter().any(|team| team.is_empty()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Teams can't be empty".into(),
+        ));
+    }
+
+    let members = match_entry.members();
+    if members.iter().collect::<BTreeSet<_>>().len() != members.len() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Match contains a repeated agent".into(),
+        ));
+    }
+
+    let seats = match_entry.seats();
+    if let ValidateCallbackResult::Invalid(error) = validate_players::<G>(&seats)? {
+        return Ok(ValidateCallbackResult::Invalid(error));
+    }
+
+    let options = match match_entry.options.clone() {
+        None => G::GameOptions::default(),
+        Some(options_bytes) => match G::GameOptions::try_from(options_bytes) {
+            Ok(options) => options,
+            Err(_) => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Couldn't deserialize the options of the match".into(),
+                ))
+            }
+        },
+    };
+
+    if let Err(error) = G::validate_options(&options, &seats) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid game options: {:?}",
+            error
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -2,14 +2,102 @@ use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
 use std::convert::TryFrom;
 
+use crate::move_context::MoveContext;
+use crate::time_control::MoveClock;
+
 #[hdk_entry(id = "game_move_entry")]
 #[derive(Clone)]
 pub struct GameMoveEntry {
     pub game_hash: EntryHashB64,
+    // Seat that makes the move, the one that the game sees as its author
     pub author_pub_key: AgentPubKeyB64,
-    pub game_move: SerializedBytes,
+    // Member of the seat that signed the move, which in team games can be any teammate
+    #[serde(default)]
+    pub signed_by: Option<AgentPubKeyB64>,
+    pub game_move: MoveType,
     pub resulting_game_state: SerializedBytes,
     pub previous_move_hash: Option<HeaderHashB64>,
+    // Only present if the game has time control
+    #[serde(default)]
+    pub clock: Option<MoveClock>,
+    // State of the protocols of the mixin in the moment the move was made
+    #[serde(default)]
+    pub context: MoveContext,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MoveType {
+    // Move defined by the game, serialized
+    Game(SerializedBytes),
+    // Finishes the game, as the time of the player on turn has run out
+    ClaimTimeoutVictory,
+    // Finishes the game, as the players on turn have been inactive for too long
+    ClaimForfeit,
+    // Finishes the game, giving up
+    Resign,
+    // Proposes the rest of players to finish the game in a draw
+    OfferDraw,
+    // Finishes the game in a draw, answering the draw offer of the previous move
+    AcceptDraw,
+    // Answers the draw offer of the previous move, continuing the game
+    DeclineDraw,
+    // Asks the rest of players to revert the given number of last game moves
+    RequestTakeback {
+        moves: u32,
+    },
+    // Reverts the game moves requested in the takeback request of the previous move
+    AcceptTakeback,
+    // Answers the takeback request of the previous move, continuing the game
+    DeclineTakeback,
+    // Commits to a move defined by the game without revealing it, with the hash of the move and a secret salt
+    CommitMove {
+        commitment: Vec<u8>,
+    },
+    // Reveals the move defined by the game committed to in the given commit move
+    RevealMove {
+        commit_move_hash: HeaderHashB64,
+        game_move: SerializedBytes,
+        salt: Vec<u8>,
+    },
+    // Commits to the SHA-256 hash of a secret for the current round of the random beacon
+    CommitSeed {
+        commitment: Vec<u8>,
+    },
+    // Reveals the secret committed to in the current round of the random beacon
+    RevealSeed {
+        secret: Vec<u8>,
+    },
+    // Seals the move defined by the game for the current simultaneous round, with the hash of the move and a secret salt
+    SealRoundMove {
+        commitment: Vec<u8>,
+    },
+    // Reveals the move sealed for the current simultaneous round
+    RevealRoundMove {
+        game_move: SerializedBytes,
+        salt: Vec<u8>,
+    },
+}
+
+impl MoveType {
+    /**
+     * Whether the move is defined by the game, and so it has to follow the turn order
+     */
+    pub fn is_game_move(&self) -> bool {
+        matches!(self, MoveType::Game(_) | MoveType::RevealMove { .. })
+    }
+
+    /**
+     * Whether no more moves can be made after this one
+     */
+    pub fn finishes_game(&self) -> bool {
+        matches!(
+            self,
+            MoveType::ClaimTimeoutVictory
+                | MoveType::ClaimForfeit
+                | MoveType::Resign
+                | MoveType::AcceptDraw
+        )
+    }
 }
 
 // IO structs
@@ -17,4 +105,6 @@ pub struct GameMoveEntry {
 pub struct MoveInfo {
     pub header_hash: HeaderHashB64,
     pub game_move_entry: GameMoveEntry,
+    // Whether the move was reverted by an accepted takeback
+    pub reverted: bool,
 }
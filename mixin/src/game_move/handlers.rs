@@ -1,12 +1,30 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 
 use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
 
 use crate::{
-    game::{build_game_state, get_game, verify_we_see_previous_move_hash},
-    game_move::{GameMoveEntry, MoveInfo},
+    commit_reveal::{verify_can_commit, verify_can_reveal, verify_has_no_open_commitment},
+    game::{
+        apply_move_type, get_current_game_state, get_game, is_finished, players_on_clock,
+        verify_it_is_authors_turn, verify_we_see_previous_move_hash,
+    },
+    game_index::index_finished_game,
+    game_match::continue_match,
+    game_move::{get_commit_move, GameMoveEntry, MoveInfo, MoveType},
+    game_result::create_game_result,
+    inactivity::{last_activity_at, verify_can_claim_forfeit},
+    meta_moves::verify_can_answer_draw_offer,
+    move_context::{get_move, next_move_context},
+    outcome::mixin_move_outcome,
+    rounds::verify_plays_in_rounds,
     signal::{self, SignalPayload},
+    takeback::{
+        effective_move_indexes, state_after_takeback, verify_can_answer_takeback_request,
+        verify_can_request_takeback,
+    },
+    time_control::{next_move_clock, now, timed_out_players},
     turn_based_game::TurnBasedGame,
 };
 
@@ -21,57 +39,11 @@ pub fn create_move<G: TurnBasedGame>(
     previous_move_hash: Option<HeaderHashB64>,
     game_move: G::GameMove,
 ) -> ExternResult<HeaderHashB64> {
-    let moves = get_moves_entries(game_hash.clone())?;
-    verify_we_see_previous_move_hash(&moves, previous_move_hash.clone())?;
-
-    let game = get_game(game_hash.clone())?;
-    let only_moves: Vec<GameMoveEntry> = moves.iter().map(|m| m.1.clone()).collect();
-
-    let game_state = build_game_state::<G>(&game, &only_moves)?;
-
     let move_bytes: SerializedBytes = game_move
-        .clone()
         .try_into()
         .or(Err(WasmError::Guest("Couldn't serialize game move".into())))?;
 
-    let new_game_state = G::apply_move(
-        game_state,
-        game_move,
-        agent_info()?.agent_latest_pubkey.into(),
-    )?;
-
-    let game_state_bytes: SerializedBytes = new_game_state.try_into().or(Err(WasmError::Guest(
-        "Couldn't serialize game state".into(),
-    )))?;
-
-    let game_move = GameMoveEntry {
-        game_hash: game_hash.clone().into(),
-        author_pub_key: agent_info()?.agent_latest_pubkey.into(),
-        game_move: move_bytes,
-        resulting_game_state: game_state_bytes,
-        previous_move_hash: previous_move_hash.clone(),
-    };
-
-    let header_hash = create_entry(&game_move)?;
-
-    let move_hash = hash_entry(&game_move)?;
-
-    create_link(
-        EntryHash::from(game_hash.clone()),
-        move_hash.clone(),
-        LinkType(0),
-        game_to_move_tag(),
-    )?;
-
-    // Sends the newly created move to all opponents of the game
-    let signal = SignalPayload::NewMove(MoveInfo {
-        header_hash: header_hash.clone().into(),
-        game_move_entry: game_move,
-    });
-
-    signal::send_signal_to_players(game, signal)?;
-
-    Ok(header_hash.into())
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::Game(move_bytes))
 }
 
 /**
@@ -79,12 +51,16 @@ pub fn create_move<G: TurnBasedGame>(
  */
 pub fn get_game_moves(game_hash: EntryHashB64) -> ExternResult<Vec<MoveInfo>> {
     let moves = get_moves_entries(game_hash)?;
+    let only_moves: Vec<GameMoveEntry> = moves.iter().map(|m| m.1.clone()).collect();
+    let effective_indexes = effective_move_indexes(&only_moves);
 
     Ok(moves
         .into_iter()
-        .map(|(header_hash, move_entry)| MoveInfo {
+        .enumerate()
+        .map(|(index, (header_hash, move_entry))| MoveInfo {
             header_hash,
             game_move_entry: move_entry,
+            reverted: !effective_indexes.contains(&index),
         })
         .collect())
 }
@@ -95,7 +71,7 @@ pub fn get_game_moves(game_hash: EntryHashB64) -> ExternResult<Vec<MoveInfo>> {
 pub fn get_moves_entries(
     game_hash: EntryHashB64,
 ) -> ExternResult<Vec<(HeaderHashB64, GameMoveEntry)>> {
-    let links = get_links(EntryHash::from(game_hash), Some(game_to_move_tag()))?;
+    let links = get_links(EntryHash::from(game_hash.clone()), Some(game_to_move_tag()))?;
 
     let get_inputs = links
         .into_iter()
@@ -117,55 +93,332 @@ pub fn get_moves_entries(
         })
         .collect::<ExternResult<Vec<(HeaderHashB64, GameMoveEntry)>>>()?;
 
+    // Links to moves of other games can't be part of the chain of this game
+    moves.retain(|(_, move_entry)| move_entry.game_hash.eq(&game_hash));
+
     order_moves(&mut moves)
 }
 
-/** Private helpers */
+/** Helper functions */
+
+/**
+ * Returns the last move of the given game, or None if it has no moves yet
+ *
+ * The links from the game to its moves carry the hash of each move and of its previous move,
+ * so the chain is ordered from the links and only its last move is fetched
+ *
+ * If the fetched move is not the entry that its link points to, or is not a move of this game,
+ * the tags of the links can't be trusted and all the moves are fetched to be ordered
+ */
+pub(crate) fn get_last_move(
+    game_hash: EntryHashB64,
+) -> ExternResult<Option<(HeaderHashB64, GameMoveEntry)>> {
+    let links = get_links(EntryHash::from(game_hash.clone()), Some(game_to_move_tag()))?;
+
+    let maybe_tags = links
+        .iter()
+        .map(|link| parse_game_to_move_tag(&link.tag).map(|tag| (tag, link.target.clone())))
+        .collect::<Option<Vec<(GameToMoveTag, EntryHash)>>>();
+
+    let tags = match maybe_tags {
+        Some(tags) => tags,
+        // Moves linked without their hashes in the tag have to be fetched to be ordered
+        None => return Ok(get_moves_entries(game_hash)?.pop()),
+    };
+
+    let chain = tags
+        .iter()
+        .map(|(tag, _)| (tag.move_hash.clone(), tag.previous_move_hash.clone()))
+        .collect();
+
+    let last_move_hash = match order_chain(chain)?.pop() {
+        None => return Ok(None),
+        Some(last_move_hash) => last_move_hash,
+    };
+
+    let element = get(
+        HeaderHash::from(last_move_hash.clone()),
+        GetOptions::default(),
+    )?
+    .ok_or(WasmError::Guest("Couldn't get move".into()))?;
+    let last_move: GameMoveEntry = element
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("Couldn't deserialize move".into()))?;
+
+    let is_linked_move = tags.iter().any(|(tag, target)| {
+        tag.move_hash.eq(&last_move_hash) && element.header().entry_hash().eq(&Some(target))
+    });
+
+    match is_linked_move && last_move.game_hash.eq(&game_hash) {
+        true => Ok(Some((last_move_hash, last_move))),
+        false => Ok(get_moves_entries(game_hash)?.pop()),
+    }
+}
+
+/**
+ * Commits the move of the given type after verifying that it can be made, and notifies the opponents
+ */
+pub(crate) fn commit_move<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    move_type: MoveType,
+) -> ExternResult<HeaderHashB64> {
+    let last_move = get_last_move(game_hash.clone())?;
+    verify_we_see_previous_move_hash(last_move.as_ref(), previous_move_hash.clone())?;
+
+    let game = get_game(game_hash.clone())?;
+    let last_move_entry = last_move.as_ref().map(|(_, m)| m);
+
+    let game_state = get_current_game_state::<G>(game_hash.clone(), &game, last_move_entry)?;
+
+    if is_finished(&game_state, last_move_entry) {
+        return Err(WasmError::Guest(
+            "Game is already finished: cannot make any more moves".into(),
+        ));
+    }
+
+    // In team games, we move for our seat
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+    let author: AgentPubKeyB64 = game
+        .seat_of(&my_pub_key)
+        .ok_or(WasmError::Guest("We are not playing this game".into()))?;
+    let context = next_move_context(&game.players, last_move.as_ref())?;
+    let last_game_mover = context.last_game_mover.as_ref();
+    let players_on_clock = players_on_clock(&game_state, &game, &context);
+
+    let clock = match game.settings.time_control.clone() {
+        None => None,
+        Some(time_control) => {
+            let clock = next_move_clock(&game, &time_control, last_move.as_ref())?;
+            let timed_out =
+                timed_out_players(&time_control, &clock, players_on_clock.clone(), now()?);
+
+            match move_type {
+                MoveType::ClaimTimeoutVictory => {
+                    if timed_out.is_empty() || timed_out.contains(&author) {
+                        return Err(WasmError::Guest(
+                            "Cannot claim the victory: the time of the rival has not run out"
+                                .into(),
+                        ));
+                    }
+                }
+                _ => {
+                    if timed_out.contains(&author) {
+                        return Err(WasmError::Guest(
+                            "Cannot make move: our time has run out".into(),
+                        ));
+                    }
+                }
+            }
+
+            Some(clock)
+        }
+    };
+
+    match &move_type {
+        MoveType::Game(_) => {
+            verify_plays_in_rounds::<G>(false)?;
+            verify_it_is_authors_turn(&game_state, &game, last_game_mover, &author)?;
+            verify_has_no_open_commitment(&context, &author)?
+        }
+        MoveType::RevealMove {
+            commit_move_hash,
+            game_move,
+            salt,
+        } => {
+            verify_plays_in_rounds::<G>(false)?;
+            verify_it_is_authors_turn(&game_state, &game, last_game_mover, &author)?;
+
+            let commit = get_commit_move(&game_hash, commit_move_hash)?.ok_or(WasmError::Guest(
+                "The commit move is not a move of this game".into(),
+            ))?;
+
+            verify_can_reveal(
+                &commit,
+                commit_move_hash,
+                &context,
+                &author,
+                game_move,
+                salt,
+            )?
+        }
+        MoveType::CommitMove { commitment } => verify_can_commit(&context, &author, commitment)?,
+        MoveType::CommitSeed { commitment } => {
+            context
+                .random_beacon
+                .verify_can_commit(&game.players, &author, commitment)?
+        }
+        MoveType::RevealSeed { secret } => {
+            context
+                .random_beacon
+                .verify_can_reveal(&game.players, &author, secret)?
+        }
+        MoveType::SealRoundMove { commitment } => {
+            context
+                .round
+                .verify_can_seal::<G>(&game.players, &author, commitment)?
+        }
+        MoveType::RevealRoundMove { game_move, salt } => {
+            context
+                .round
+                .verify_can_reveal::<G>(&game.players, &author, game_move, salt)?
+        }
+        MoveType::ClaimForfeit => verify_can_claim_forfeit(
+            &game,
+            &players_on_clock,
+            &author,
+            last_activity_at(&game, &context, &players_on_clock),
+            now()?,
+        )?,
+        MoveType::AcceptDraw | MoveType::DeclineDraw => {
+            verify_can_answer_draw_offer(last_move_entry, &author)?
+        }
+        MoveType::RequestTakeback { moves } => verify_can_request_takeback(&context, *moves)?,
+        MoveType::AcceptTakeback | MoveType::DeclineTakeback => {
+            verify_can_answer_takeback_request(last_move_entry, &author)?
+        }
+        MoveType::ClaimTimeoutVictory | MoveType::Resign | MoveType::OfferDraw => {}
+    }
+
+    let new_game_state = match move_type {
+        MoveType::AcceptTakeback => state_after_takeback::<G>(&game, &context, &get_move)?,
+        _ => apply_move_type(game_state, &move_type, &author, &game.players, &context)?,
+    };
+
+    let outcome = mixin_move_outcome(&game, &author, &move_type)
+        .or_else(|| new_game_state.outcome(&game.players));
+
+    let game_state_bytes: SerializedBytes = new_game_state.try_into().or(Err(WasmError::Guest(
+        "Couldn't serialize game state".into(),
+    )))?;
+
+    let game_move = GameMoveEntry {
+        game_hash: game_hash.clone().into(),
+        author_pub_key: author,
+        signed_by: Some(my_pub_key),
+        game_move: move_type,
+        resulting_game_state: game_state_bytes,
+        previous_move_hash: previous_move_hash.clone(),
+        clock,
+        context,
+    };
+
+    let header_hash = create_entry(&game_move)?;
+
+    let move_hash = hash_entry(&game_move)?;
+
+    create_link(
+        EntryHash::from(game_hash.clone()),
+        move_hash.clone(),
+        LinkType(0),
+        game_to_move_tag_with(GameToMoveTag {
+            move_hash: header_hash.clone().into(),
+            previous_move_hash: previous_move_hash.clone(),
+        })?,
+    )?;
+
+    // Sends the newly created move to all opponents and spectators of the game
+    let signal = SignalPayload::NewMove(MoveInfo {
+        header_hash: header_hash.clone().into(),
+        game_move_entry: game_move,
+        reverted: false,
+    });
+
+    signal::send_signal_to_players_and_spectators(game_hash.clone(), game.clone(), signal)?;
+
+    // The author of the move that finishes the game commits its result
+    if let Some(outcome) = outcome {
+        index_finished_game(game_hash.clone(), &game)?;
+        let game_result_hash = create_game_result(
+            game_hash.clone(),
+            &game,
+            header_hash.clone().into(),
+            outcome.clone(),
+        )?;
+
+        if let Some(match_hash) = game.match_hash.clone() {
+            continue_match(match_hash, game_hash, game_result_hash, outcome)?;
+        }
+    }
+
+    Ok(header_hash.into())
+}
 
 /**
  * Returns the moves ordered following the previous_move_address
  *
- * Returns error if in any case the chain of moves is not valid
+ * Returns error if in any case the chain of moves is not valid: if there are forks, repeated moves,
+ * no first move or several of them, or gaps in the chain
  */
-fn order_moves(
+pub(crate) fn order_moves(
     moves: &mut Vec<(HeaderHashB64, GameMoveEntry)>,
 ) -> ExternResult<Vec<(HeaderHashB64, GameMoveEntry)>> {
-    if moves.is_empty() {
+    let chain = moves
+        .iter()
+        .map(|(move_hash, move_entry)| (move_hash.clone(), move_entry.previous_move_hash.clone()))
+        .collect();
+
+    let ordered_hashes = order_chain(chain)?;
+
+    // move_hash -> move_entry
+    let mut moves_map: HashMap<HeaderHashB64, GameMoveEntry> = moves.drain(..).collect();
+
+    Ok(ordered_hashes
+        .into_iter()
+        .filter_map(|move_hash| {
+            moves_map
+                .remove(&move_hash)
+                .map(|move_entry| (move_hash, move_entry))
+        })
+        .collect())
+}
+
+/** Private helpers */
+
+/**
+ * Returns the hashes of the moves ordered following their previous move hashes, given pairs of (move_hash, previous_move_hash)
+ *
+ * Returns error if in any case the chain of moves is not valid
+ */
+fn order_chain(
+    chain: Vec<(HeaderHashB64, Option<HeaderHashB64>)>,
+) -> ExternResult<Vec<HeaderHashB64>> {
+    if chain.is_empty() {
         return Ok(vec![]);
     }
 
     // previous_move_hash -> next_move_hash
     let mut next_moves_map: HashMap<HeaderHashB64, HeaderHashB64> = HashMap::new();
-    // move_hash -> move_entry
-    let mut moves_map: HashMap<HeaderHashB64, GameMoveEntry> = HashMap::new();
+    let mut move_hashes: HashSet<HeaderHashB64> = HashSet::new();
 
     let mut first_move: Option<HeaderHashB64> = None;
 
-    for move_entry in moves {
-        if let Some(previous_move) = move_entry.1.previous_move_hash.clone() {
+    for (move_hash, maybe_previous_move) in chain {
+        if let Some(previous_move) = maybe_previous_move {
             if next_moves_map.contains_key(&previous_move) {
                 return Err(WasmError::Guest(
                     "There are two moves pointing to the same next move".into(),
                 ));
             }
 
-            next_moves_map.insert(previous_move, move_entry.0.clone());
+            next_moves_map.insert(previous_move, move_hash.clone());
         } else {
             if let Some(_) = first_move {
                 return Err(WasmError::Guest(
                     "There are two first moves in this list".into(),
                 ));
             }
-            first_move = Some(move_entry.0.clone());
+            first_move = Some(move_hash.clone());
         }
 
-        if moves_map.contains_key(&move_entry.0) {
+        if move_hashes.contains(&move_hash) {
             return Err(WasmError::Guest(
                 "There are two entries with the same hash in this list".into(),
             ));
         }
 
-        moves_map.insert(move_entry.0.clone(), move_entry.1.clone());
+        move_hashes.insert(move_hash);
     }
 
     match first_move {
@@ -175,29 +428,52 @@ fn order_moves(
             ))
         }
         Some(first_move_hash) => {
-            let mut ordered_moves: Vec<(HeaderHashB64, GameMoveEntry)> = vec![];
+            let mut ordered_hashes: Vec<HeaderHashB64> = vec![];
 
             let mut maybe_next_move_hash: Option<HeaderHashB64> = Some(first_move_hash);
 
             while let Some(next_move_hash) = maybe_next_move_hash {
-                match moves_map.get(&next_move_hash) {
-                    None => Err(WasmError::Guest(
-                        "There are missing moves in the list".into(),
-                    )),
-                    Some(move_entry) => {
-                        ordered_moves.push((next_move_hash.clone(), move_entry.clone()));
-                        Ok(())
-                    }
-                }?;
-
                 maybe_next_move_hash = next_moves_map.get(&next_move_hash).cloned();
+                ordered_hashes.push(next_move_hash);
+            }
+
+            // The chain stops at the first gap, leaving out the moves that come after it: returning it would make
+            // a stale move look like the last one of the game, so new moves would be built on an outdated state
+            if ordered_hashes.len() < move_hashes.len() {
+                return Err(WasmError::Guest(
+                    "There are missing moves in the list".into(),
+                ));
             }
 
-            Ok(ordered_moves)
+            Ok(ordered_hashes)
         }
     }
 }
 
+/**
+ * Tag of the link from the game to each of its moves, so that the chain of moves can be ordered without getting them
+ */
+#[derive(Serialize, Deserialize, Debug, SerializedBytes)]
+struct GameToMoveTag {
+    move_hash: HeaderHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+}
+
 fn game_to_move_tag() -> LinkTag {
     LinkTag::from(String::from("game->move").as_bytes().to_vec())
 }
+
+fn game_to_move_tag_with(tag: GameToMoveTag) -> ExternResult<LinkTag> {
+    let bytes = SerializedBytes::try_from(tag)?;
+
+    let mut tag_bytes = game_to_move_tag().0;
+    tag_bytes.extend(bytes.bytes());
+
+    Ok(LinkTag(tag_bytes))
+}
+
+fn parse_game_to_move_tag(tag: &LinkTag) -> Option<GameToMoveTag> {
+    let bytes = tag.0.strip_prefix(game_to_move_tag().0.as_slice())?;
+
+    GameToMoveTag::try_from(SerializedBytes::from(UnsafeBytes::from(bytes.to_vec()))).ok()
+}
@@ -1,7 +1,9 @@
 pub mod entry;
 pub mod handlers;
-//pub mod validation;
+#[cfg(test)]
+mod tests;
+pub mod validation;
 
 pub use entry::*;
 pub use handlers::*;
-//pub use validation::*;
\ No newline at end of file
+pub use validation::*;
@@ -0,0 +1,146 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use super::{order_moves, GameMoveEntry, MoveType};
+
+/**
+ * Positions of the moves of a chain of the given length, in a random order
+ */
+fn shuffled_chain(min_length: usize) -> impl Strategy<Value = Vec<usize>> {
+    (min_length..30usize)
+        .prop_flat_map(|length| Just((0..length).collect::<Vec<usize>>()).prop_shuffle())
+}
+
+fn move_hash(position: usize) -> HeaderHashB64 {
+    let mut bytes = (position as u32).to_le_bytes().to_vec();
+    bytes.resize(32, 0);
+    HeaderHash::from_raw_32(bytes).into()
+}
+
+fn game_move(previous_move_hash: Option<HeaderHashB64>) -> GameMoveEntry {
+    GameMoveEntry {
+        game_hash: EntryHash::from_raw_32(vec![0; 32]).into(),
+        author_pub_key: AgentPubKey::from_raw_32(vec![0; 32]).into(),
+        signed_by: None,
+        game_move: MoveType::Resign,
+        resulting_game_state: SerializedBytes::from(UnsafeBytes::from(vec![])),
+        previous_move_hash,
+        clock: None,
+        context: Default::default(),
+    }
+}
+
+/**
+ * Move at the given position of a valid chain, pointing to the move before it
+ */
+fn chain_move(position: usize) -> (HeaderHashB64, GameMoveEntry) {
+    let previous_move_hash = match position {
+        0 => None,
+        _ => Some(move_hash(position - 1)),
+    };
+
+    (move_hash(position), game_move(previous_move_hash))
+}
+
+fn chain_moves(positions: &Vec<usize>) -> Vec<(HeaderHashB64, GameMoveEntry)> {
+    positions
+        .iter()
+        .map(|position| chain_move(*position))
+        .collect()
+}
+
+fn ordered_hashes(
+    mut moves: Vec<(HeaderHashB64, GameMoveEntry)>,
+) -> ExternResult<Vec<HeaderHashB64>> {
+    Ok(order_moves(&mut moves)?
+        .into_iter()
+        .map(|(hash, _)| hash)
+        .collect())
+}
+
+fn error_message(result: ExternResult<Vec<HeaderHashB64>>) -> String {
+    match result {
+        Err(WasmError::Guest(message)) => message,
+        Err(error) => panic!("Unexpected error {:?}", error),
+        Ok(hashes) => panic!(
+            "Expected an error, but the moves were ordered: {:?}",
+            hashes
+        ),
+    }
+}
+
+proptest! {
+    #[test]
+    fn orders_shuffled_chains(positions in shuffled_chain(0)) {
+        let expected: Vec<HeaderHashB64> = (0..positions.len()).map(move_hash).collect();
+
+        prop_assert_eq!(ordered_hashes(chain_moves(&positions)).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_forks(positions in shuffled_chain(2), fork_from: Index, insert_at: Index) {
+        let length = positions.len();
+        let mut moves = chain_moves(&positions);
+
+        // Branching from the last move would just extend the chain
+        let fork = game_move(Some(move_hash(fork_from.index(length - 1))));
+        moves.insert(insert_at.index(length + 1), (move_hash(length), fork));
+
+        prop_assert_eq!(
+            error_message(ordered_hashes(moves)),
+            "There are two moves pointing to the same next move"
+        );
+    }
+
+    #[test]
+    fn rejects_missing_moves(positions in shuffled_chain(2), missing: Index) {
+        let length = positions.len();
+
+        // Without the last move the chain is still valid, only shorter
+        let missing_position = missing.index(length - 1);
+        let remaining: Vec<usize> = positions
+            .into_iter()
+            .filter(|position| *position != missing_position)
+            .collect();
+
+        let expected = match missing_position {
+            0 => "There is no first move in this list",
+            _ => "There are missing moves in the list",
+        };
+
+        prop_assert_eq!(error_message(ordered_hashes(chain_moves(&remaining))), expected);
+    }
+
+    #[test]
+    fn rejects_two_first_moves(positions in shuffled_chain(1), insert_at: Index) {
+        let length = positions.len();
+        let mut moves = chain_moves(&positions);
+
+        moves.insert(insert_at.index(length + 1), (move_hash(length), game_move(None)));
+
+        prop_assert_eq!(
+            error_message(ordered_hashes(moves)),
+            "There are two first moves in this list"
+        );
+    }
+
+    #[test]
+    fn rejects_repeated_hashes(positions in shuffled_chain(1), repeated: Index, insert_at: Index) {
+        let length = positions.len();
+        let mut moves = chain_moves(&positions);
+
+        // Extends the chain, but with the hash of a move that is already in it
+        let repeated_move = game_move(Some(move_hash(length - 1)));
+        moves.insert(
+            insert_at.index(length + 1),
+            (move_hash(repeated.index(length)), repeated_move),
+        );
+
+        prop_assert_eq!(
+            error_message(ordered_hashes(moves)),
+            "There are two entries with the same hash in this list"
+        );
+    }
+}
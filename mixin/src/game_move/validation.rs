@@ -1,131 +1,328 @@
-/**use hdk::prelude::holo_hash::*;
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
 
-use super::GameMoveEntry;
-use crate::game::{apply_move, GameEntry};
-use crate::turn_based_game::{GameStatus, TurnBasedGame};
+use super::{GameMoveEntry, MoveType};
+use crate::commit_reveal::{verify_can_commit, verify_can_reveal, verify_has_no_open_commitment};
+use crate::game::{
+    apply_move, is_finished, players_on_clock, verify_it_is_authors_turn, GameEntry,
+};
+use crate::inactivity::{last_activity_at, verify_can_claim_forfeit};
+use crate::meta_moves::verify_can_answer_draw_offer;
+use crate::move_context::{get_move, MoveContext};
+use crate::rounds::verify_plays_in_rounds;
+use crate::takeback::{
+    state_after_takeback, verify_can_answer_takeback_request, verify_can_request_takeback,
+};
+use crate::time_control::{
+    following_clock, initial_clock, timed_out_players, timestamp_to_date_time,
+};
+use crate::turn_based_game::TurnBasedGame;
 
 /**
 * Validates the move, getting the game
 */
 pub fn validate_game_move_entry<G: TurnBasedGame>(
-    validate_data: ValidateData,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+    move_entry: GameMoveEntry,
 ) -> ExternResult<ValidateCallbackResult> {
-    let author = validate_data.element.header().author();
+    trace!("Validating move: {:?}", move_entry);
 
-    let maybe_move_entry: Option<GameMoveEntry> = validate_data.element.entry().to_app_option()?;
+    let entry_hashed = must_get_entry(EntryHash::from(move_entry.game_hash.clone()))?;
+    trace!("Validating move, game entry: {:?}", entry_hashed);
 
-    if let Some(move_entry) = maybe_move_entry {
-        trace!("Validating move: {:?}", move_entry);
+    let game: GameEntry = entry_hashed.as_content().try_into()?;
 
-        if author.clone() != AgentPubKey::from(move_entry.author_pub_key.clone()) {
-            return Ok(ValidateCallbackResult::Invalid(
-                "This move is not signed by its author".into(),
-            ));
-        }
-
-        let entry_hashed = must_get_entry(EntryHash::from(move_entry.game_hash.clone()))?;
-        trace!("Validating move, game entry: {:?}", entry_hashed);
-
-        let game: GameEntry = entry_hashed.as_content().try_into()?;
+    if !game.players.contains(&move_entry.author_pub_key) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The author of the move is not playing the game".into(),
+        ));
+    }
 
-        trace!("Validating move, game entry: {:?}", game);
-        if !game.players.contains(&move_entry.author_pub_key) {
-            return Ok(ValidateCallbackResult::Invalid(
-                "The author of the move is not playing the game".into(),
-            ));
-        }
+    let author: AgentPubKeyB64 = author.into();
 
-        let maybe_last_move_hash: Option<HeaderHashB64> = move_entry.previous_move_hash.clone();
+    if move_entry.signed_by != Some(author.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The signer recorded in the move is not the author of its header".into(),
+        ));
+    }
 
-        let mut previous_game_state = G::initial(game.players.clone());
-        let mut maybe_last_move: Option<GameMoveEntry> = None;
+    // In team games, any member of a seat can move for it
+    if game.seat_of(&author) != Some(move_entry.author_pub_key.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This move is not signed by a member of the seat of its author".into(),
+        ));
+    }
 
-        if let Some(last_move_hash) = maybe_last_move_hash {
-            let move_element = must_get_valid_element(last_move_hash.clone().into())?;
+    let mut previous_game_state = G::initial(game.players.clone(), game.options::<G>()?);
+    let mut maybe_last_move: Option<GameMoveEntry> = None;
+    let mut maybe_last_move_timestamp: Option<Timestamp> = None;
+    let mut context = MoveContext::default();
 
-            let maybe_game_move: Option<GameMoveEntry> = move_element.entry().to_app_option()?;
+    if let Some(last_move_hash) = move_entry.previous_move_hash.clone() {
+        let move_element = must_get_valid_element(last_move_hash.clone().into())?;
+        trace!("Validating move, previous move element: {:?}", move_element);
 
-            trace!("Validating move, previous move element: {:?}", move_element);
+        let maybe_game_move: Option<GameMoveEntry> = move_element.entry().to_app_option()?;
 
-            if let Some(game_move) = maybe_game_move {
-                trace!("Validating move, previous move entry: {:?}", game_move);
-                previous_game_state = G::try_from(game_move.clone().resulting_game_state).or(
+        match maybe_game_move {
+            Some(game_move) if game_move.game_hash.eq(&move_entry.game_hash) => {
+                previous_game_state = G::try_from(game_move.resulting_game_state.clone()).or(
                     Err(WasmError::Guest("Couldn't deserialize game state".into())),
                 )?;
+                // The context of the previous move is carried forward, so there is no need to walk back the chain of moves
+                context = game_move.context.following(
+                    &game.players,
+                    &last_move_hash,
+                    move_element.header().timestamp(),
+                    &game_move,
+                    &get_move,
+                )?;
                 maybe_last_move = Some(game_move);
-            } else {
-                return Ok(ValidateCallbackResult::UnresolvedDependencies(vec![
-                    HeaderHash::from(last_move_hash).into(),
-                ]));
+                maybe_last_move_timestamp = Some(move_element.header().timestamp());
+            }
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The previous move is not a move of this game".into(),
+                ))
             }
         }
+    }
 
-        validate_it_is_authors_turn(&move_entry.author_pub_key, &maybe_last_move, &game.players)?;
+    if is_finished(&previous_game_state, maybe_last_move.as_ref()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Game is already finished: cannot make any more moves".into(),
+        ));
+    }
+
+    if !move_entry.context.eq(&context) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The context for this move is not the actual correct one".into(),
+        ));
+    }
+
+    let now = timestamp_to_date_time(timestamp);
+
+    if let Err(error) = verify_can_make_move(
+        &game,
+        &previous_game_state,
+        &context,
+        maybe_last_move.as_ref(),
+        &move_entry,
+        now,
+        &|commit_move_hash| get_commit_move(&move_entry.game_hash, commit_move_hash),
+    ) {
+        return Ok(ValidateCallbackResult::Invalid(format!("{:?}", error)));
+    }
 
-        // Get the outcome of the game
+    match game.settings.time_control.clone() {
+        None => {
+            if move_entry.clock.is_some() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Moves of games without time control can't have a clock".into(),
+                ));
+            }
+        }
+        Some(time_control) => {
+            let expected_clock = match (maybe_last_move.as_ref(), maybe_last_move_timestamp) {
+                (Some(last_move), Some(last_move_timestamp)) => following_clock(
+                    &time_control,
+                    &game.players,
+                    last_move,
+                    timestamp_to_date_time(last_move_timestamp),
+                )?,
+                _ => initial_clock(&time_control, &game),
+            };
 
-        if let GameStatus::Finished = previous_game_state.status() {
-            return Err(WasmError::Guest(format!(
-                "Game is already finished: cannot make any more moves",
-            )));
+            if !move_entry.clock.eq(&Some(expected_clock)) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The clock for this move is not the actual correct one".into(),
+                ));
+            }
         }
+    }
 
-        let new_game_state = apply_move(previous_game_state, &move_entry)?;
+    if let Err(error) = verify_time_left(
+        &game,
+        players_on_clock(&previous_game_state, &game, &context),
+        &move_entry,
+        now,
+    ) {
+        return Ok(ValidateCallbackResult::Invalid(format!("{:?}", error)));
+    }
 
-        let new_game_state_bytes: SerializedBytes = new_game_state.try_into().or(Err(
-            WasmError::Guest("Error serializing new game state".into()),
-        ))?;
+    let new_game_state = match move_entry.game_move {
+        MoveType::AcceptTakeback => state_after_takeback::<G>(&game, &context, &get_move),
+        _ => apply_move(previous_game_state, &move_entry, &game.players, &context),
+    };
 
-        if !move_entry.resulting_game_state.eq(&new_game_state_bytes) {
-            return Ok(ValidateCallbackResult::Invalid(
-                "The resulting game state for this move is not the actual correct one".into(),
-            ));
+    let new_game_state = match new_game_state {
+        Ok(game_state) => game_state,
+        Err(error) => {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Invalid move: {:?}",
+                error
+            )))
         }
+    };
 
-        Ok(ValidateCallbackResult::Valid)
-    } else {
+    let new_game_state_bytes: SerializedBytes = new_game_state.try_into().or(Err(
+        WasmError::Guest("Error serializing new game state".into()),
+    ))?;
+
+    if !move_entry.resulting_game_state.eq(&new_game_state_bytes) {
         return Ok(ValidateCallbackResult::Invalid(
-            "Game move validation was called without an entry being present".into(),
+            "The resulting game state for this move is not the actual correct one".into(),
         ));
     }
+
+    Ok(ValidateCallbackResult::Valid)
 }
 
 /** Helper functions */
 
 /**
- * Validate that it's the turn of the author of the move
+ * Returns error if the author of the move can't make it, given the state of the game and the context of the move
+ *
+ * Doesn't call any host function, getting the commit moves that the reveals refer to with the given function,
+ * so that the `GameSimulator` follows the same rules as the validation
  */
-fn validate_it_is_authors_turn(
-    author_pub_key: &AgentPubKeyB64,
-    maybe_last_move: &Option<GameMoveEntry>,
-    players: &Vec<AgentPubKeyB64>,
+pub(crate) fn verify_can_make_move<G: TurnBasedGame>(
+    game: &GameEntry,
+    game_state: &G,
+    context: &MoveContext,
+    last_move: Option<&GameMoveEntry>,
+    move_entry: &GameMoveEntry,
+    now: DateTime<Utc>,
+    get_commit_move: &dyn Fn(&HeaderHashB64) -> ExternResult<Option<GameMoveEntry>>,
 ) -> ExternResult<()> {
-    let maybe_last_player_index = match maybe_last_move {
-        Some(last_move) => players
-            .iter()
-            .position(|p| p.clone() == last_move.author_pub_key),
-        None => None,
-    };
+    let author = &move_entry.author_pub_key;
+
+    // Moves not defined by the game don't pass the turn, and moves reverted by a takeback don't count,
+    // so it's computed from the last effective game move
+    let last_game_mover = context.last_game_mover.as_ref();
+
+    let players_on_clock = players_on_clock(game_state, game, context);
 
-    // Get the index of the player whose turn it is
-    let player_index = match maybe_last_player_index {
-        Some(last_player_index) => {
-            let new_index = last_player_index + 1;
+    match &move_entry.game_move {
+        MoveType::Game(_) => {
+            verify_plays_in_rounds::<G>(false)?;
+            verify_it_is_authors_turn(game_state, game, last_game_mover, author)?;
+            verify_has_no_open_commitment(context, author)
+        }
+        MoveType::RevealMove {
+            commit_move_hash,
+            game_move,
+            salt,
+        } => {
+            verify_plays_in_rounds::<G>(false)?;
+            verify_it_is_authors_turn(game_state, game, last_game_mover, author)?;
+
+            let commit = get_commit_move(commit_move_hash)?.ok_or(WasmError::Guest(
+                "The commit move is not a move of this game".into(),
+            ))?;
+
+            verify_can_reveal(&commit, commit_move_hash, context, author, game_move, salt)
+        }
+        MoveType::CommitMove { commitment } => verify_can_commit(context, author, commitment),
+        MoveType::CommitSeed { commitment } => {
+            context
+                .random_beacon
+                .verify_can_commit(&game.players, author, commitment)
+        }
+        MoveType::RevealSeed { secret } => {
+            context
+                .random_beacon
+                .verify_can_reveal(&game.players, author, secret)
+        }
+        MoveType::SealRoundMove { commitment } => {
+            context
+                .round
+                .verify_can_seal::<G>(&game.players, author, commitment)
+        }
+        MoveType::RevealRoundMove { game_move, salt } => {
+            context
+                .round
+                .verify_can_reveal::<G>(&game.players, author, game_move, salt)
+        }
+        MoveType::ClaimForfeit => verify_can_claim_forfeit(
+            game,
+            &players_on_clock,
+            author,
+            last_activity_at(game, context, &players_on_clock),
+            now,
+        ),
+        MoveType::AcceptDraw | MoveType::DeclineDraw => {
+            verify_can_answer_draw_offer(last_move, author)
+        }
+        MoveType::RequestTakeback { moves } => verify_can_request_takeback(context, *moves),
+        MoveType::AcceptTakeback | MoveType::DeclineTakeback => {
+            verify_can_answer_takeback_request(last_move, author)
+        }
+        MoveType::ClaimTimeoutVictory | MoveType::Resign | MoveType::OfferDraw => Ok(()),
+    }
+}
 
-            match new_index >= players.len() {
-                true => 0,
-                false => new_index,
+/**
+ * Returns error if the time of the author of the move has run out at the given moment, given the clock of the move,
+ * or if they claim a timeout victory and the time of none of the players on clock has run out
+ */
+pub(crate) fn verify_time_left(
+    game: &GameEntry,
+    players_on_clock: Vec<AgentPubKeyB64>,
+    move_entry: &GameMoveEntry,
+    now: DateTime<Utc>,
+) -> ExternResult<()> {
+    let author = &move_entry.author_pub_key;
+
+    let (time_control, clock) = match (
+        game.settings.time_control.as_ref(),
+        move_entry.clock.as_ref(),
+    ) {
+        (Some(time_control), Some(clock)) => (time_control, clock),
+        _ => {
+            return match move_entry.game_move {
+                MoveType::ClaimTimeoutVictory => Err(WasmError::Guest(
+                    "Cannot claim a timeout victory in a game without time control".into(),
+                )),
+                _ => Ok(()),
             }
         }
-        None => 0,
     };
 
-    if players[player_index] != author_pub_key.clone() {
-        return Err(WasmError::Guest(
-            "It's not the turn of the author of the move".into(),
-        ));
+    let timed_out = timed_out_players(time_control, clock, players_on_clock, now);
+
+    match move_entry.game_move {
+        MoveType::ClaimTimeoutVictory => {
+            if timed_out.is_empty() || timed_out.contains(author) {
+                return Err(WasmError::Guest(
+                    "Cannot claim the victory: the time of the rival had not run out".into(),
+                ));
+            }
+        }
+        _ => {
+            if timed_out.contains(author) {
+                return Err(WasmError::Guest(
+                    "The time of the author of the move had run out".into(),
+                ));
+            }
+        }
     }
 
     Ok(())
-} **/
+}
+
+/**
+ * Gets the commit move with the given hash, or None if it's not a move of the given game
+ */
+pub(crate) fn get_commit_move(
+    game_hash: &EntryHashB64,
+    commit_move_hash: &HeaderHashB64,
+) -> ExternResult<Option<GameMoveEntry>> {
+    let commit: Option<GameMoveEntry> = must_get_valid_element(commit_move_hash.clone().into())?
+        .entry()
+        .to_app_option()?;
+
+    Ok(commit.filter(|commit| commit.game_hash.eq(game_hash)))
+}
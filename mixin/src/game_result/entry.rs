@@ -0,0 +1,75 @@
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use crate::game::{members, seat_of};
+use crate::turn_based_game::GameOutcome;
+
+/**
+ * Committed by the author of the last move of a game, once the game has finished
+ */
+#[hdk_entry(id = "game_result_entry")]
+#[derive(Clone)]
+pub struct GameResultEntry {
+    pub game_hash: EntryHashB64,
+    pub players: Vec<AgentPubKeyB64>,
+    pub last_move_hash: HeaderHashB64,
+    // Outcome of each seat of the game, which applies to all the members of its team
+    pub outcome: GameOutcome,
+    #[serde(default)]
+    pub teammates: BTreeMap<AgentPubKeyB64, Vec<AgentPubKeyB64>>,
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PlayerResult {
+    Won,
+    Lost,
+    Drawn,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GameHistoryFilter {
+    pub opponent: Option<AgentPubKeyB64>,
+    pub result: Option<PlayerResult>,
+    // Number of matching results to skip, starting from the most recent one
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GameResultInfo {
+    pub game_result_hash: EntryHashB64,
+    pub game_result: GameResultEntry,
+    #[serde(with = "ts_milliseconds")]
+    pub finished_at: DateTime<Utc>,
+}
+
+impl GameResultEntry {
+    /**
+     * Result of the game for the given player, if they played it, which is the result of their seat in team games
+     */
+    pub fn result_for(&self, player: &AgentPubKeyB64) -> Option<PlayerResult> {
+        let seat = seat_of(&self.players, &self.teammates, player)?;
+
+        if self.outcome.winners.contains(&seat) {
+            Some(PlayerResult::Won)
+        } else if self.outcome.losers.contains(&seat) {
+            Some(PlayerResult::Lost)
+        } else if self.outcome.draws.contains(&seat) {
+            Some(PlayerResult::Drawn)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * All the agents that played the game, including the teammates of each seat
+     */
+    pub fn members(&self) -> Vec<AgentPubKeyB64> {
+        members(&self.players, &self.teammates)
+    }
+}
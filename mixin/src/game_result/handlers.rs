@@ -0,0 +1,186 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::convert::TryFrom;
+
+use crate::game::GameEntry;
+use crate::time_control::timestamp_to_date_time;
+use crate::turn_based_game::GameOutcome;
+
+use super::{GameHistoryFilter, GameResultEntry, GameResultInfo, PlayerResult};
+
+/** Public handlers */
+
+/**
+ * Gets the results of the games that we have played, from the most recent to the oldest one
+ *
+ * The links to the results are filtered and paginated with the opponents and the result stored in their tags,
+ * so that only the results in the requested page are fetched
+ */
+pub fn get_my_game_history(filter: GameHistoryFilter) -> ExternResult<Vec<GameResultInfo>> {
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    let mut links = get_links(
+        AgentPubKey::from(my_pub_key).into(),
+        Some(game_results_tag()),
+    )?;
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    let limit = filter.limit.unwrap_or(links.len());
+
+    let links: Vec<Link> = links
+        .into_iter()
+        .filter(|link| match parse_game_results_tag(&link.tag) {
+            Some(tag) => tag.matches(&filter),
+            None => false,
+        })
+        .skip(filter.offset)
+        .take(limit)
+        .collect();
+
+    let get_inputs = links
+        .iter()
+        .map(|l| GetInput::new(l.target.clone().into(), GetOptions::default()))
+        .collect();
+
+    let elements = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+    let mut game_results = vec![];
+
+    for (link, maybe_element) in links.into_iter().zip(elements.into_iter()) {
+        if let Some(element) = maybe_element {
+            let game_result: GameResultEntry = element
+                .entry()
+                .to_app_option()?
+                .ok_or(WasmError::Guest("Could not convert game result".into()))?;
+
+            game_results.push(GameResultInfo {
+                game_result_hash: link.target.into(),
+                game_result,
+                finished_at: timestamp_to_date_time(link.timestamp),
+            });
+        }
+    }
+
+    Ok(game_results)
+}
+
+/**
+ * Gets the result of the given game, or None if it has not finished yet
+ */
+pub fn get_game_result_entry(game_hash: EntryHashB64) -> ExternResult<Option<GameResultEntry>> {
+    let links = get_links(EntryHash::from(game_hash), Some(game_result_tag()))?;
+
+    match links.first() {
+        None => Ok(None),
+        Some(link) => {
+            let element = get(link.target.clone(), GetOptions::default())?
+                .ok_or(WasmError::Guest("Couldn't get game result".into()))?;
+
+            Ok(element.entry().to_app_option()?)
+        }
+    }
+}
+
+/** Helper functions */
+
+/**
+ * Commits the result of the finished game, linking it from the game and from all its players
+ */
+pub(crate) fn create_game_result(
+    game_hash: EntryHashB64,
+    game: &GameEntry,
+    last_move_hash: HeaderHashB64,
+    outcome: GameOutcome,
+) -> ExternResult<EntryHashB64> {
+    let game_result = GameResultEntry {
+        game_hash: game_hash.clone(),
+        players: game.players.clone(),
+        last_move_hash,
+        outcome,
+        teammates: game.teammates.clone(),
+    };
+
+    create_entry(&game_result)?;
+
+    let game_result_hash = hash_entry(&game_result)?;
+
+    create_link(
+        EntryHash::from(game_hash),
+        game_result_hash.clone(),
+        LinkType(0),
+        game_result_tag(),
+    )?;
+
+    for agent in game.members() {
+        let tag = GameResultsTag {
+            result: game_result.result_for(&agent),
+            opponents: game_result
+                .members()
+                .into_iter()
+                .filter(|member| !member.eq(&agent))
+                .collect(),
+        };
+
+        create_link(
+            AgentPubKey::from(agent).into(),
+            game_result_hash.clone(),
+            LinkType(0),
+            game_results_tag_with(tag)?,
+        )?;
+    }
+
+    Ok(game_result_hash.into())
+}
+
+/** Private helpers */
+
+fn game_result_tag() -> LinkTag {
+    LinkTag::new("game_result")
+}
+
+fn game_results_tag() -> LinkTag {
+    LinkTag::new("game_results")
+}
+
+/**
+ * Contents of the tag of the links from each player to the results of their games
+ */
+#[derive(Serialize, Deserialize, Debug, SerializedBytes)]
+struct GameResultsTag {
+    result: Option<PlayerResult>,
+    // The rest of members of the game, teammates included
+    opponents: Vec<AgentPubKeyB64>,
+}
+
+impl GameResultsTag {
+    fn matches(&self, filter: &GameHistoryFilter) -> bool {
+        let matches_opponent = match filter.opponent.as_ref() {
+            Some(opponent) => self.opponents.contains(opponent),
+            None => true,
+        };
+        let matches_result = match filter.result.as_ref() {
+            Some(result) => self.result.as_ref() == Some(result),
+            None => true,
+        };
+
+        matches_opponent && matches_result
+    }
+}
+
+/**
+ * The "game_results" tag followed by the serialized contents, so that all of them are found by its prefix
+ */
+fn game_results_tag_with(tag: GameResultsTag) -> ExternResult<LinkTag> {
+    let bytes = SerializedBytes::try_from(tag)?;
+
+    let mut tag_bytes = game_results_tag().0;
+    tag_bytes.extend(bytes.bytes());
+
+    Ok(LinkTag(tag_bytes))
+}
+
+fn parse_game_results_tag(tag: &LinkTag) -> Option<GameResultsTag> {
+    let bytes = tag.0.strip_prefix(game_results_tag().0.as_slice())?;
+
+    GameResultsTag::try_from(SerializedBytes::from(UnsafeBytes::from(bytes.to_vec()))).ok()
+}
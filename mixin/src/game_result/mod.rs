@@ -0,0 +1,7 @@
+mod entry;
+mod handlers;
+mod validation;
+
+pub use entry::*;
+pub use handlers::*;
+pub use validation::*;
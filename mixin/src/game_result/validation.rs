@@ -0,0 +1,41 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use super::GameResultEntry;
+use crate::game::GameEntry;
+use crate::outcome::final_outcome;
+use crate::turn_based_game::TurnBasedGame;
+
+/**
+ * Validates the game result, returning error if:
+ *
+ * - The author of the result is not one of the players
+ * - The players or the outcome don't match the ones of the game after its last move
+ */
+pub fn validate_game_result_entry<G: TurnBasedGame>(
+    author: AgentPubKey,
+    game_result: GameResultEntry,
+) -> ExternResult<ValidateCallbackResult> {
+    let entry_hashed = must_get_entry(EntryHash::from(game_result.game_hash.clone()))?;
+    let game: GameEntry = entry_hashed.as_content().try_into()?;
+
+    if game.seat_of(&AgentPubKeyB64::from(author)).is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The author of the game result is not playing the game".into(),
+        ));
+    }
+
+    if !game.players.eq(&game_result.players) || !game.teammates.eq(&game_result.teammates) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The players of the game result are not the players of the game".into(),
+        ));
+    }
+
+    match final_outcome::<G>(&game, &game_result.game_hash, &game_result.last_move_hash) {
+        Ok(Some(outcome)) if outcome.eq(&game_result.outcome) => Ok(ValidateCallbackResult::Valid),
+        Ok(_) => Ok(ValidateCallbackResult::Invalid(
+            "The outcome of the game result is not the actual outcome of the game".into(),
+        )),
+        Err(error) => Ok(ValidateCallbackResult::Invalid(format!("{:?}", error))),
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    current_games::remove_current_game,
+    game::GameEntry,
+    game_move::{commit_move, MoveType},
+    move_context::MoveContext,
+    time_control::timestamp_to_date_time,
+    turn_based_game::TurnBasedGame,
+};
+
+/** Public handlers */
+
+/**
+ * Finishes the game by claiming the victory, given that the players on turn have been inactive
+ * for longer than the inactivity threshold of the game, and removes it from the current games
+ */
+pub fn claim_forfeit<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    let header_hash = commit_move::<G>(
+        game_hash.clone(),
+        previous_move_hash,
+        MoveType::ClaimForfeit,
+    )?;
+
+    remove_current_game(game_hash)?;
+
+    Ok(header_hash)
+}
+
+/** Helper functions */
+
+/**
+ * Returns error if the claimant can't claim the forfeit of the players on turn at the given moment
+ */
+pub(crate) fn verify_can_claim_forfeit(
+    game: &GameEntry,
+    players_on_turn: &Vec<AgentPubKeyB64>,
+    claimant: &AgentPubKeyB64,
+    last_activity_at: DateTime<Utc>,
+    at: DateTime<Utc>,
+) -> ExternResult<()> {
+    let inactivity_threshold_ms = game
+        .settings
+        .inactivity_threshold_ms
+        .ok_or(WasmError::Guest(
+            "This game has no inactivity threshold".into(),
+        ))?;
+
+    if players_on_turn.contains(claimant) {
+        return Err(WasmError::Guest(
+            "Cannot claim a forfeit while it's our turn".into(),
+        ));
+    }
+
+    let inactive_ms = at
+        .signed_duration_since(last_activity_at)
+        .num_milliseconds();
+
+    if inactive_ms <= inactivity_threshold_ms {
+        return Err(WasmError::Guest(
+            "Cannot claim a forfeit: the inactivity threshold has not been reached yet".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/**
+ * Returns the moment of the last activity of the players on turn, given the context of the next move:
+ * the last move defined by the game, which started their turn, or any later move made by themselves
+ *
+ * Moves of the rest of players, like draw offers, don't reset the inactivity of the players on turn
+ * Returns the creation of the game if there is no activity yet
+ */
+pub(crate) fn last_activity_at(
+    game: &GameEntry,
+    context: &MoveContext,
+    players_on_turn: &Vec<AgentPubKeyB64>,
+) -> DateTime<Utc> {
+    context
+        .last_game_move_at
+        .into_iter()
+        .chain(
+            players_on_turn
+                .iter()
+                .filter_map(|player| context.last_move_at.get(player).cloned()),
+        )
+        .max()
+        .map(timestamp_to_date_time)
+        .unwrap_or(game.created_at)
+}
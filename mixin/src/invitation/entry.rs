@@ -0,0 +1,41 @@
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::convert::TryFrom;
+
+use crate::game::GameSettings;
+
+#[hdk_entry(id = "invitation")]
+#[derive(Clone)]
+pub struct Invitation {
+    pub inviter: AgentPubKeyB64,
+    // All the players of the game, in the order in which they will move, inviter included
+    pub players: Vec<AgentPubKeyB64>,
+    #[serde(with = "ts_milliseconds")]
+    pub created_at: DateTime<Utc>,
+    // Settings of the game that starts once all invitees accept
+    #[serde(default)]
+    pub settings: GameSettings,
+}
+
+impl Invitation {
+    /**
+     * All the players that need to accept the invitation for the game to start
+     */
+    pub fn invitees(&self) -> Vec<AgentPubKeyB64> {
+        self.players
+            .iter()
+            .filter(|p| !p.eq(&&self.inviter))
+            .cloned()
+            .collect()
+    }
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvitationInfo {
+    pub invitation_hash: EntryHashB64,
+    pub invitation: Invitation,
+    pub accepted_by: Vec<AgentPubKeyB64>,
+}
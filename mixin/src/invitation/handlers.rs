@@ -0,0 +1,325 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::{AgentPubKeyB64, EntryHashB64};
+use hdk::prelude::*;
+
+use crate::game::{create_game_with_settings, GameSettings};
+use crate::signal::{send_signal_to_agents, SignalPayload};
+
+use super::{Invitation, InvitationInfo};
+
+/** Public handlers */
+
+/**
+ * Invites the given players to a new game, which will be played with the given settings
+ *
+ * The players must include ourselves, in the position in which we want to move
+ */
+pub fn send_invitation(
+    players: Vec<AgentPubKeyB64>,
+    settings: GameSettings,
+) -> ExternResult<EntryHashB64> {
+    let now = sys_time()?.as_seconds_and_nanos();
+
+    let date_time = DateTime::from_utc(NaiveDateTime::from_timestamp(now.0, now.1), Utc);
+
+    let invitation = Invitation {
+        inviter: agent_info()?.agent_latest_pubkey.into(),
+        players: players.clone(),
+        created_at: date_time,
+        settings,
+    };
+
+    create_entry(&invitation)?;
+
+    let invitation_hash = hash_entry(&invitation)?;
+
+    for agent in players.clone() {
+        create_link(
+            AgentPubKey::from(agent).into(),
+            invitation_hash.clone(),
+            LinkType(0),
+            pending_invitation_tag(),
+        )?;
+    }
+
+    let invitation_hash_b64 = EntryHashB64::from(invitation_hash);
+
+    let signal = SignalPayload::InvitationReceived {
+        invitation_hash: invitation_hash_b64.clone(),
+        invitation,
+    };
+
+    send_signal_to_agents(players, signal)?;
+
+    Ok(invitation_hash_b64)
+}
+
+/**
+ * Accepts the invitation
+ *
+ * The inviter creates the game when they receive the acceptance of the last invitee,
+ * so that only one game gets created even if several invitees accept at the same time
+ * Returns the hash of the game if it has already been created
+ */
+pub fn accept_invitation(invitation_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let invitation = get_invitation(invitation_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if !invitation.invitees().contains(&my_pub_key) {
+        return Err(WasmError::Guest("We are not invited to this game".into()));
+    }
+
+    if let Some(game_hash) = get_invitation_game(invitation_hash.clone())? {
+        return Ok(Some(game_hash));
+    }
+
+    verify_is_not_rejected(invitation_hash.clone())?;
+
+    if !get_accepted_by(invitation_hash.clone())?.contains(&my_pub_key) {
+        create_link(
+            EntryHash::from(invitation_hash.clone()),
+            AgentPubKey::from(my_pub_key.clone()).into(),
+            LinkType(0),
+            accepted_invitation_tag(),
+        )?;
+    }
+
+    let signal = SignalPayload::InvitationAccepted {
+        invitation_hash,
+        invitee: my_pub_key,
+    };
+    send_signal_to_agents(invitation.players, signal)?;
+
+    Ok(None)
+}
+
+/**
+ * Creates the game of the invitation if all its invitees have accepted it
+ *
+ * Only the inviter can start the game, which they do when they receive the acceptance of the last invitee,
+ * and it's only created once: returns the hash of the game if it was already created
+ */
+pub fn start_invited_game(invitation_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let invitation = get_invitation(invitation_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if !invitation.inviter.eq(&my_pub_key) {
+        return Err(WasmError::Guest(
+            "Only the inviter can start the game of the invitation".into(),
+        ));
+    }
+
+    if let Some(game_hash) = get_invitation_game(invitation_hash.clone())? {
+        return Ok(Some(game_hash));
+    }
+
+    verify_is_not_rejected(invitation_hash.clone())?;
+
+    let accepted_by = get_accepted_by(invitation_hash.clone())?;
+
+    let all_accepted = invitation
+        .invitees()
+        .iter()
+        .all(|invitee| accepted_by.contains(invitee));
+
+    if !all_accepted {
+        return Ok(None);
+    }
+
+    let game_hash =
+        create_game_with_settings(invitation.players.clone(), invitation.settings.clone())?;
+
+    create_link(
+        EntryHash::from(invitation_hash.clone()),
+        EntryHash::from(game_hash.clone()),
+        LinkType(0),
+        invitation_game_tag(),
+    )?;
+
+    remove_pending_invitation(invitation_hash, invitation.players)?;
+
+    Ok(Some(game_hash))
+}
+
+/**
+ * Rejects the invitation, which stops being pending for all its players
+ */
+pub fn reject_invitation(invitation_hash: EntryHashB64) -> ExternResult<()> {
+    let invitation = get_invitation(invitation_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if !invitation.players.contains(&my_pub_key) {
+        return Err(WasmError::Guest("We are not invited to this game".into()));
+    }
+
+    create_link(
+        EntryHash::from(invitation_hash.clone()),
+        AgentPubKey::from(my_pub_key.clone()).into(),
+        LinkType(0),
+        rejected_invitation_tag(),
+    )?;
+
+    remove_pending_invitation(invitation_hash.clone(), invitation.players.clone())?;
+
+    let signal = SignalPayload::InvitationRejected {
+        invitation_hash,
+        invitee: my_pub_key,
+    };
+    send_signal_to_agents(invitation.players, signal)?;
+
+    Ok(())
+}
+
+/**
+ * Gets all the invitations that we sent or received and that have not started or been rejected yet
+ */
+pub fn get_my_pending_invitations() -> ExternResult<Vec<InvitationInfo>> {
+    let links = get_links(
+        agent_info()?.agent_latest_pubkey.into(),
+        Some(pending_invitation_tag()),
+    )?;
+
+    let get_inputs = links
+        .into_iter()
+        .map(|l| GetInput::new(l.target.into(), GetOptions::default()))
+        .collect();
+
+    let elements = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+    let mut invitations = vec![];
+
+    for element in elements.into_iter().filter_map(|m| m) {
+        let invitation: Invitation = element
+            .entry()
+            .to_app_option()?
+            .ok_or(WasmError::Guest("Could not convert invitation".into()))?;
+
+        let invitation_hash: EntryHashB64 = element
+            .header()
+            .entry_hash()
+            .ok_or(WasmError::Guest("Bad create invitation header".into()))?
+            .clone()
+            .into();
+
+        invitations.push(InvitationInfo {
+            accepted_by: get_accepted_by(invitation_hash.clone())?,
+            invitation_hash,
+            invitation,
+        });
+    }
+
+    Ok(invitations)
+}
+
+pub fn get_invitation(invitation_hash: EntryHashB64) -> ExternResult<Invitation> {
+    let element = get(EntryHash::from(invitation_hash), GetOptions::default())?.ok_or(
+        WasmError::Guest("There is no invitation at this hash".into()),
+    )?;
+
+    element
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("Couldn't deserialize invitation".into()))
+}
+
+/** Helper functions */
+
+/**
+ * Starts the game of the invitation if we are its inviter, called when any invitee accepts it
+ */
+pub(crate) fn handle_invitation_accepted(invitation_hash: EntryHashB64) -> ExternResult<()> {
+    let invitation = get_invitation(invitation_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if invitation.inviter.eq(&my_pub_key) {
+        start_invited_game(invitation_hash)?;
+    }
+
+    Ok(())
+}
+
+/** Private helpers */
+
+/**
+ * Gets the game that was created for the invitation, if it has already started
+ */
+fn get_invitation_game(invitation_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let links = get_links(
+        EntryHash::from(invitation_hash),
+        Some(invitation_game_tag()),
+    )?;
+
+    // Only the inviter creates the game, and only once, so there can't be more than one
+    Ok(links
+        .into_iter()
+        .min_by_key(|link| link.timestamp)
+        .map(|link| link.target.into()))
+}
+
+fn verify_is_not_rejected(invitation_hash: EntryHashB64) -> ExternResult<()> {
+    let rejections = get_links(
+        EntryHash::from(invitation_hash),
+        Some(rejected_invitation_tag()),
+    )?;
+
+    match rejections.is_empty() {
+        true => Ok(()),
+        false => Err(WasmError::Guest(
+            "The invitation has already been rejected".into(),
+        )),
+    }
+}
+
+fn get_accepted_by(invitation_hash: EntryHashB64) -> ExternResult<Vec<AgentPubKeyB64>> {
+    let links = get_links(
+        EntryHash::from(invitation_hash),
+        Some(accepted_invitation_tag()),
+    )?;
+
+    Ok(links
+        .into_iter()
+        .map(|link| link.target.retype(holo_hash::hash_type::Agent).into())
+        .collect())
+}
+
+/**
+ * Deletes the links that make the invitation appear as pending for all of its players
+ */
+fn remove_pending_invitation(
+    invitation_hash: EntryHashB64,
+    players: Vec<AgentPubKeyB64>,
+) -> ExternResult<()> {
+    let invitation_hash = EntryHash::from(invitation_hash);
+
+    for agent in players {
+        let links = get_links(
+            AgentPubKey::from(agent).into(),
+            Some(pending_invitation_tag()),
+        )?;
+
+        for link in links
+            .into_iter()
+            .filter(|link| link.target.eq(&invitation_hash))
+        {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn pending_invitation_tag() -> LinkTag {
+    LinkTag::new("pending_invitation")
+}
+
+fn accepted_invitation_tag() -> LinkTag {
+    LinkTag::new("accepted_invitation")
+}
+
+fn rejected_invitation_tag() -> LinkTag {
+    LinkTag::new("rejected_invitation")
+}
+
+fn invitation_game_tag() -> LinkTag {
+    LinkTag::new("invitation_game")
+}
@@ -0,0 +1,31 @@
+use hdk::prelude::*;
+
+use super::Invitation;
+use crate::game::validate_players;
+use crate::TurnBasedGame;
+
+/**
+ * Validates the invitation, returning error if:
+ *
+ * - The author of the invitation is not its inviter
+ * - The inviter is not one of the players
+ * - The players would not be valid for a game
+ */
+pub fn validate_invitation_entry<G: TurnBasedGame>(
+    author: AgentPubKey,
+    invitation: Invitation,
+) -> ExternResult<ValidateCallbackResult> {
+    if author != AgentPubKey::from(invitation.inviter.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This invitation is not signed by its inviter".into(),
+        ));
+    }
+
+    if !invitation.players.contains(&invitation.inviter) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The inviter must be one of the players of the game".into(),
+        ));
+    }
+
+    validate_players::<G>(&invitation.players)
+}
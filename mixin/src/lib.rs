@@ -1,29 +1,88 @@
-//use hdk::prelude::*;
-
+mod commit_reveal;
 mod current_games;
 mod game;
+mod game_index;
+mod game_match;
 mod game_move;
+mod game_result;
+mod inactivity;
+mod invitation;
+mod matchmaking;
+mod meta_moves;
+#[cfg(feature = "test-support")]
+mod mock_hdk;
+mod move_context;
+mod outcome;
+mod random_beacon;
+mod ratings;
+mod rematch;
+mod rounds;
 mod signal;
+mod simulator;
+mod spectators;
+mod takeback;
+mod time_control;
+mod tournament;
 mod turn_based_game;
+mod validate;
 
 mod mixin;
 
-/*#[hdk_extern]
-pub fn validate(op:Op) -> ExternResult<ValidateCallbackResult>{
-    match op{
-     Ok(ValidateCallbackResult::Valid)
-    }
-}*/
-
-pub use current_games::{get_my_current_games, remove_my_current_game, remove_current_game};
+pub use commit_reveal::{commit_hidden_move, move_commitment, reveal_move};
+pub use current_games::{get_my_current_games, remove_current_game, remove_my_current_game};
 pub use game::{
-    create_game, get_game, get_game_state, GameEntry //validate_game_entry, GameEntry,
+    create_game, create_game_with_options, create_game_with_settings, create_team_game, get_game,
+    get_game_state, get_game_status, validate_game_entry, GameEntry, GameSettings,
+};
+pub use game_index::{
+    get_finished_games, get_ongoing_games, get_recent_games, GameIndexQuery, IndexedGame,
+    IndexedGamesPage,
+};
+pub use game_match::{
+    create_match, get_match, get_match_state, validate_match_entry, MatchEntry, MatchState,
 };
 pub use game_move::{
-    create_move, get_game_moves, GameMoveEntry, MoveInfo, //validate_game_move_entry, GameMoveEntry, MoveInfo,
+    create_move, get_game_moves, validate_game_move_entry, GameMoveEntry, MoveInfo, MoveType,
+};
+pub use game_result::{
+    get_game_result_entry, get_my_game_history, validate_game_result_entry, GameHistoryFilter,
+    GameResultEntry, GameResultInfo, PlayerResult,
+};
+pub use inactivity::claim_forfeit;
+pub use invitation::{
+    accept_invitation, get_invitation, get_my_pending_invitations, reject_invitation,
+    send_invitation, start_invited_game, validate_invitation_entry, Invitation, InvitationInfo,
+};
+pub use matchmaking::{
+    cancel_game_request, get_game_request, get_open_game_requests, join_game_request,
+    publish_game_request, start_requested_game, validate_game_request_entry, GameRequest,
+    GameRequestFilter, GameRequestInfo,
 };
+pub use meta_moves::{accept_draw, decline_draw, offer_draw, resign};
 pub use mixin::*;
+#[cfg(feature = "test-support")]
+pub use mock_hdk::{MockNetwork, RemoteHandler};
+pub use move_context::MoveContext;
+pub use outcome::get_game_result;
+pub use random_beacon::{commit_seed, reveal_seed, GameRng};
+pub use ratings::{
+    get_leaderboard, get_rating, update_ratings, validate_rating_change_entry, LeaderboardEntry,
+    RatingChangeEntry,
+};
+pub use rematch::{accept_rematch, get_rematch, offer_rematch};
+pub use rounds::{reveal_round_move, seal_round_move};
+pub use signal::SignalPayload;
+pub use simulator::{simulated_move_hash, simulated_players, GameSimulator};
+pub use spectators::{get_game_spectators, unwatch_game, watch_game};
+pub use takeback::{accept_takeback, decline_takeback, request_takeback};
+pub use time_control::{
+    claim_timeout_victory, get_remaining_time, MoveClock, RemainingTime, TimeControl,
+};
+pub use tournament::{
+    create_tournament, get_tournament, get_tournament_state, get_tournaments,
+    register_for_tournament, start_next_tournament_round, validate_tournament_entry,
+    validate_tournament_round, Pairing, TournamentEntry, TournamentFormat, TournamentInfo,
+    TournamentRound, TournamentStanding, TournamentState,
+};
 pub use turn_based_game::*;
-
-
-
+pub use validate::validate_turn_based_game_op;
@@ -0,0 +1,38 @@
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::convert::TryFrom;
+
+/**
+ * Published by an agent that is looking for a rival to play a game with
+ */
+#[hdk_entry(id = "game_request")]
+#[derive(Clone)]
+pub struct GameRequest {
+    pub requester: AgentPubKeyB64,
+    pub game_type: Option<String>,
+    pub rating_band: Option<String>,
+    #[serde(with = "ts_milliseconds")]
+    pub created_at: DateTime<Utc>,
+    // Number of players of the game, requester included, which starts once enough rivals have joined
+    #[serde(default = "default_game_request_players")]
+    pub players: usize,
+}
+
+fn default_game_request_players() -> usize {
+    2
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GameRequestFilter {
+    pub game_type: Option<String>,
+    pub rating_band: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GameRequestInfo {
+    pub game_request_hash: EntryHashB64,
+    pub game_request: GameRequest,
+}
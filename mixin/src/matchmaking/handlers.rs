@@ -0,0 +1,312 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::{AgentPubKeyB64, EntryHashB64};
+use hdk::prelude::*;
+
+use crate::game::create_game;
+use crate::signal::{send_signal_to_agents, SignalPayload};
+use crate::turn_based_game::TurnBasedGame;
+
+use super::{GameRequest, GameRequestFilter, GameRequestInfo};
+
+/** Public handlers */
+
+/**
+ * Publishes that we are looking for a game, so that other agents can join it
+ *
+ * The game starts with the minimum number of players allowed by the game, and at least two
+ */
+pub fn publish_game_request<G: TurnBasedGame>(
+    filter: GameRequestFilter,
+) -> ExternResult<EntryHashB64> {
+    let now = sys_time()?.as_seconds_and_nanos();
+
+    let date_time = DateTime::from_utc(NaiveDateTime::from_timestamp(now.0, now.1), Utc);
+
+    let game_request = GameRequest {
+        requester: agent_info()?.agent_latest_pubkey.into(),
+        game_type: filter.game_type.clone(),
+        rating_band: filter.rating_band.clone(),
+        created_at: date_time,
+        players: G::min_players().unwrap_or(2).max(2),
+    };
+
+    create_entry(&game_request)?;
+
+    let game_request_hash = hash_entry(&game_request)?;
+
+    let path = matchmaking_path(&filter);
+    path.ensure()?;
+
+    create_link(
+        path.hash()?,
+        game_request_hash.clone(),
+        LinkType(0),
+        game_request_tag(),
+    )?;
+
+    Ok(game_request_hash.into())
+}
+
+/**
+ * Gets all the game requests that are still waiting for a rival, for the given game type and rating band
+ */
+pub fn get_open_game_requests(filter: GameRequestFilter) -> ExternResult<Vec<GameRequestInfo>> {
+    let links = get_links(matchmaking_path(&filter).hash()?, Some(game_request_tag()))?;
+
+    let get_inputs = links
+        .into_iter()
+        .map(|l| GetInput::new(l.target.into(), GetOptions::default()))
+        .collect();
+
+    let elements = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+    let mut game_requests = vec![];
+
+    for element in elements.into_iter().filter_map(|m| m) {
+        let game_request: GameRequest = element
+            .entry()
+            .to_app_option()?
+            .ok_or(WasmError::Guest("Could not convert game request".into()))?;
+
+        let game_request_hash = element
+            .header()
+            .entry_hash()
+            .ok_or(WasmError::Guest("Bad create game request header".into()))?;
+
+        game_requests.push(GameRequestInfo {
+            game_request_hash: game_request_hash.clone().into(),
+            game_request,
+        });
+    }
+
+    Ok(game_requests)
+}
+
+/**
+ * Joins the game request, and returns its game if it has already started
+ *
+ * The requester creates the game when they receive the join, so that only one game gets created even if several
+ * rivals join at the same time: the first ones to join play it, and the requester is the first player to move
+ */
+pub fn join_game_request(game_request_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let game_request = get_game_request(game_request_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if game_request.requester.eq(&my_pub_key) {
+        return Err(WasmError::Guest("Cannot join our own game request".into()));
+    }
+
+    if let Some(game_hash) = get_requested_game(game_request_hash.clone())? {
+        return Ok(Some(game_hash));
+    }
+
+    if !get_joiners(game_request_hash.clone())?.contains(&my_pub_key) {
+        create_link(
+            EntryHash::from(game_request_hash.clone()),
+            AgentPubKey::from(my_pub_key.clone()).into(),
+            LinkType(0),
+            game_request_join_tag(),
+        )?;
+    }
+
+    let signal = SignalPayload::GameRequestJoinReceived {
+        game_request_hash,
+        joiner: my_pub_key,
+    };
+    send_signal_to_agents(vec![game_request.requester], signal)?;
+
+    Ok(None)
+}
+
+/**
+ * Creates the game of the game request once enough rivals have joined it, or returns the game that was already created
+ *
+ * Only the requester can call it, and it's called for them when they receive the join of a rival,
+ * so it's only needed if the requester was offline at that moment
+ */
+pub fn start_requested_game(game_request_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let game_request = get_game_request(game_request_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if !game_request.requester.eq(&my_pub_key) {
+        return Err(WasmError::Guest(
+            "Only the requester can start the game of the game request".into(),
+        ));
+    }
+
+    if let Some(game_hash) = get_requested_game(game_request_hash.clone())? {
+        return Ok(Some(game_hash));
+    }
+
+    let rivals: Vec<AgentPubKeyB64> = get_joiners(game_request_hash.clone())?
+        .into_iter()
+        .take(game_request.players.saturating_sub(1))
+        .collect();
+
+    if rivals.len() + 1 < game_request.players {
+        return Ok(None);
+    }
+
+    close_game_request(game_request_hash.clone(), &game_request)?;
+
+    let mut players = vec![my_pub_key];
+    players.extend(rivals.clone());
+
+    let game_hash = create_game(players)?;
+
+    create_link(
+        EntryHash::from(game_request_hash.clone()),
+        EntryHash::from(game_hash.clone()),
+        LinkType(0),
+        game_request_game_tag(),
+    )?;
+
+    let signal = SignalPayload::GameRequestJoined {
+        game_request_hash,
+        game_hash: game_hash.clone(),
+    };
+    send_signal_to_agents(rivals, signal)?;
+
+    Ok(Some(game_hash))
+}
+
+/**
+ * Stops looking for a game with the given game request
+ */
+pub fn cancel_game_request(game_request_hash: EntryHashB64) -> ExternResult<()> {
+    let game_request = get_game_request(game_request_hash.clone())?;
+
+    if !AgentPubKey::from(game_request.requester.clone()).eq(&agent_info()?.agent_latest_pubkey) {
+        return Err(WasmError::Guest(
+            "Only the requester can cancel a game request".into(),
+        ));
+    }
+
+    close_game_request(game_request_hash, &game_request)
+}
+
+pub fn get_game_request(game_request_hash: EntryHashB64) -> ExternResult<GameRequest> {
+    let element = get(EntryHash::from(game_request_hash), GetOptions::default())?.ok_or(
+        WasmError::Guest("There is no game request at this hash".into()),
+    )?;
+
+    element
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("Couldn't deserialize game request".into()))
+}
+
+/** Helper functions */
+
+/**
+ * Starts the game of the game request if we are its requester, when a rival joins it
+ */
+pub(crate) fn handle_game_request_join(game_request_hash: EntryHashB64) -> ExternResult<()> {
+    let game_request = get_game_request(game_request_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if game_request.requester.eq(&my_pub_key) {
+        start_requested_game(game_request_hash)?;
+    }
+
+    Ok(())
+}
+
+/** Private helpers */
+
+/**
+ * Gets the game that was created for the game request, if it has already started
+ */
+fn get_requested_game(game_request_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let links = get_links(
+        EntryHash::from(game_request_hash),
+        Some(game_request_game_tag()),
+    )?;
+
+    // Only the requester creates the game, and only once, so there can't be more than one
+    Ok(links
+        .into_iter()
+        .min_by_key(|link| link.timestamp)
+        .map(|link| link.target.into()))
+}
+
+/**
+ * Gets the rivals that have joined the game request, in the order in which they joined
+ */
+fn get_joiners(game_request_hash: EntryHashB64) -> ExternResult<Vec<AgentPubKeyB64>> {
+    let mut links = get_links(
+        EntryHash::from(game_request_hash),
+        Some(game_request_join_tag()),
+    )?;
+    links.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.target.get_raw_39().cmp(b.target.get_raw_39()))
+    });
+
+    let mut joiners: Vec<AgentPubKeyB64> = vec![];
+
+    for link in links {
+        let joiner: AgentPubKeyB64 = link.target.retype(holo_hash::hash_type::Agent).into();
+        if !joiners.contains(&joiner) {
+            joiners.push(joiner);
+        }
+    }
+
+    Ok(joiners)
+}
+
+/**
+ * Deletes the link from the matchmaking path, so that the game request is no longer open
+ */
+fn close_game_request(
+    game_request_hash: EntryHashB64,
+    game_request: &GameRequest,
+) -> ExternResult<()> {
+    let filter = GameRequestFilter {
+        game_type: game_request.game_type.clone(),
+        rating_band: game_request.rating_band.clone(),
+    };
+    let game_request_hash = EntryHash::from(game_request_hash);
+
+    let link = get_links(matchmaking_path(&filter).hash()?, Some(game_request_tag()))?
+        .into_iter()
+        .find(|link| link.target.eq(&game_request_hash))
+        .ok_or(WasmError::Guest(
+            "This game request is no longer open".into(),
+        ))?;
+
+    delete_link(link.create_link_hash)?;
+
+    Ok(())
+}
+
+/**
+ * Path in which all the game requests for the given game type and rating band are anchored
+ *
+ * Each component is labelled with its field, so that a game type and a rating band with the same value get different paths
+ */
+fn matchmaking_path(filter: &GameRequestFilter) -> Path {
+    let mut components: Vec<Component> = vec![Component::from(String::from("matchmaking"))];
+
+    if let Some(game_type) = filter.game_type.clone() {
+        components.push(Component::from(format!("type:{}", game_type)));
+    }
+    if let Some(rating_band) = filter.rating_band.clone() {
+        components.push(Component::from(format!("band:{}", rating_band)));
+    }
+
+    Path::from(components)
+}
+
+fn game_request_tag() -> LinkTag {
+    LinkTag::new("game_request")
+}
+
+fn game_request_join_tag() -> LinkTag {
+    LinkTag::new("game_request_join")
+}
+
+fn game_request_game_tag() -> LinkTag {
+    LinkTag::new("game_request_game")
+}
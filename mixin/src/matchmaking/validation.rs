@@ -0,0 +1,32 @@
+use hdk::prelude::*;
+
+use super::GameRequest;
+use crate::turn_based_game::TurnBasedGame;
+
+/**
+ * Validates the game request, returning error if:
+ *
+ * - It's not published by the agent that is looking for a game
+ * - Its number of players is not within the bounds defined by the game
+ */
+pub fn validate_game_request_entry<G: TurnBasedGame>(
+    author: AgentPubKey,
+    game_request: GameRequest,
+) -> ExternResult<ValidateCallbackResult> {
+    if author != AgentPubKey::from(game_request.requester.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This game request is not signed by its requester".into(),
+        ));
+    }
+
+    let too_few = game_request.players < G::min_players().unwrap_or(2).max(2);
+    let too_many = G::max_players().map_or(false, |max| game_request.players > max);
+
+    if too_few || too_many {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Bad number of players".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
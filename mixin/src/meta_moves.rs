@@ -0,0 +1,75 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_move::{commit_move, GameMoveEntry, MoveType},
+    turn_based_game::TurnBasedGame,
+};
+
+/** Public handlers */
+
+/**
+ * Finishes the game, giving up
+ *
+ * Can be done at any moment of the game, even if it's not our turn
+ */
+pub fn resign<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::Resign)
+}
+
+/**
+ * Proposes the rest of players to finish the game in a draw
+ *
+ * The offer stays pending until the next move: if it's not an answer to the offer, it's considered declined
+ */
+pub fn offer_draw<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::OfferDraw)
+}
+
+/**
+ * Finishes the game in a draw, accepting the draw offer made in the previous move
+ */
+pub fn accept_draw<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::AcceptDraw)
+}
+
+/**
+ * Declines the draw offer made in the previous move, continuing the game
+ */
+pub fn decline_draw<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::DeclineDraw)
+}
+
+/** Helper functions */
+
+/**
+ * Returns error if the last move is not a draw offer made by another player
+ */
+pub(crate) fn verify_can_answer_draw_offer(
+    last_move: Option<&GameMoveEntry>,
+    author: &AgentPubKeyB64,
+) -> ExternResult<()> {
+    match last_move {
+        Some(last_move)
+            if last_move.game_move.eq(&MoveType::OfferDraw)
+                && !last_move.author_pub_key.eq(author) =>
+        {
+            Ok(())
+        }
+        _ => Err(WasmError::Guest(
+            "There is no draw offer from another player to answer".into(),
+        )),
+    }
+}
@@ -1,7 +1,7 @@
 use hdk::prelude::holo_hash::*;
 use hdk::prelude::*;
 
-use crate::TurnBasedGame;
+use crate::{GameSettings, TournamentFormat, TurnBasedGame};
 
 pub fn init_turn_based_games() -> ExternResult<InitCallbackResult> {
     // grant unrestricted access to accept_cap_claim so other agents can send us claims
@@ -25,6 +25,72 @@ pub struct MakeMoveInput<G: TurnBasedGame> {
     pub game_move: G::GameMove,
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct MixinMoveInput {
+    pub game_hash: EntryHashB64,
+    pub previous_move_hash: Option<HeaderHashB64>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct CommitHiddenMoveInput<G: TurnBasedGame> {
+    pub game_hash: EntryHashB64,
+    pub previous_move_hash: Option<HeaderHashB64>,
+    pub game_move: G::GameMove,
+    pub salt: Vec<u8>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct RevealMoveInput<G: TurnBasedGame> {
+    pub game_hash: EntryHashB64,
+    pub previous_move_hash: Option<HeaderHashB64>,
+    pub commit_move_hash: HeaderHashB64,
+    pub game_move: G::GameMove,
+    pub salt: Vec<u8>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SeedInput {
+    pub game_hash: EntryHashB64,
+    pub previous_move_hash: Option<HeaderHashB64>,
+    pub secret: Vec<u8>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct RequestTakebackInput {
+    pub game_hash: EntryHashB64,
+    pub previous_move_hash: Option<HeaderHashB64>,
+    pub moves: u32,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SendInvitationInput {
+    pub players: Vec<AgentPubKeyB64>,
+    #[serde(default)]
+    pub settings: GameSettings,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct CreateMatchInput<G: TurnBasedGame> {
+    pub teams: Vec<Vec<AgentPubKeyB64>>,
+    pub best_of: u32,
+    pub settings: GameSettings,
+    pub options: G::GameOptions,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct CreateTournamentInput<G: TurnBasedGame> {
+    pub name: String,
+    pub format: TournamentFormat,
+    pub settings: GameSettings,
+    pub options: G::GameOptions,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct OfferRematchInput {
+    pub game_hash: EntryHashB64,
+    pub swap_seats: bool,
+}
+
 #[macro_export]
 macro_rules! mixin_turn_based_game {
     ( $turn_based_game:ty ) => {
@@ -46,6 +112,77 @@ macro_rules! mixin_turn_based_game {
             )
         }
 
+        #[hdk_extern]
+        fn commit_hidden_move(
+            input: $crate::CommitHiddenMoveInput<$turn_based_game>,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::commit_hidden_move::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.game_move,
+                input.salt,
+            )
+        }
+
+        #[hdk_extern]
+        fn reveal_move(
+            input: $crate::RevealMoveInput<$turn_based_game>,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::reveal_move::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.commit_move_hash,
+                input.game_move,
+                input.salt,
+            )
+        }
+
+        #[hdk_extern]
+        fn seal_round_move(
+            input: $crate::CommitHiddenMoveInput<$turn_based_game>,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::seal_round_move::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.game_move,
+                input.salt,
+            )
+        }
+
+        #[hdk_extern]
+        fn reveal_round_move(
+            input: $crate::CommitHiddenMoveInput<$turn_based_game>,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::reveal_round_move::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.game_move,
+                input.salt,
+            )
+        }
+
+        #[hdk_extern]
+        fn commit_seed(
+            input: $crate::SeedInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::commit_seed::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.secret,
+            )
+        }
+
+        #[hdk_extern]
+        fn reveal_seed(
+            input: $crate::SeedInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::reveal_seed::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.secret,
+            )
+        }
+
         #[hdk_extern]
         fn get_game_moves(game_hash: EntryHashB64) -> ExternResult<Vec<$crate::MoveInfo>> {
             $crate::get_game_moves(game_hash.into())
@@ -61,45 +198,288 @@ macro_rules! mixin_turn_based_game {
             $crate::get_game(game_hash)
         }
 
-       /*  #[hdk_extern]
-        fn validate_create_entry_game_entry(
-            validate_data: ValidateData,
-        ) -> ExternResult<ValidateCallbackResult> {
-            $crate::validate_game_entry::<$turn_based_game>(validate_data)
+        #[hdk_extern]
+        fn get_remaining_time(game_hash: EntryHashB64) -> ExternResult<$crate::RemainingTime> {
+            $crate::get_remaining_time::<$turn_based_game>(game_hash)
+        }
+
+        #[hdk_extern]
+        fn claim_timeout_victory(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::claim_timeout_victory::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+            )
+        }
+
+        #[hdk_extern]
+        fn claim_forfeit(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::claim_forfeit::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn resign(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::resign::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn offer_draw(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::offer_draw::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn accept_draw(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::accept_draw::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn decline_draw(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::decline_draw::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn request_takeback(
+            input: $crate::RequestTakebackInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::request_takeback::<$turn_based_game>(
+                input.game_hash,
+                input.previous_move_hash,
+                input.moves,
+            )
+        }
+
+        #[hdk_extern]
+        fn accept_takeback(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::accept_takeback::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn decline_takeback(
+            input: $crate::MixinMoveInput,
+        ) -> ExternResult<hdk::prelude::holo_hash::HeaderHashB64> {
+            $crate::decline_takeback::<$turn_based_game>(input.game_hash, input.previous_move_hash)
+        }
+
+        #[hdk_extern]
+        fn get_recent_games(
+            query: $crate::GameIndexQuery,
+        ) -> ExternResult<$crate::IndexedGamesPage> {
+            $crate::get_recent_games(query)
+        }
+
+        #[hdk_extern]
+        fn get_ongoing_games(
+            query: $crate::GameIndexQuery,
+        ) -> ExternResult<$crate::IndexedGamesPage> {
+            $crate::get_ongoing_games(query)
+        }
+
+        #[hdk_extern]
+        fn get_finished_games(
+            query: $crate::GameIndexQuery,
+        ) -> ExternResult<$crate::IndexedGamesPage> {
+            $crate::get_finished_games(query)
+        }
+
+        #[hdk_extern]
+        fn watch_game(game_hash: EntryHashB64) -> ExternResult<()> {
+            $crate::watch_game(game_hash)
+        }
+
+        #[hdk_extern]
+        fn unwatch_game(game_hash: EntryHashB64) -> ExternResult<()> {
+            $crate::unwatch_game(game_hash)
+        }
+
+        #[hdk_extern]
+        fn get_game_spectators(
+            game_hash: EntryHashB64,
+        ) -> ExternResult<Vec<hdk::prelude::holo_hash::AgentPubKeyB64>> {
+            $crate::get_game_spectators(game_hash)
+        }
+
+        #[hdk_extern]
+        fn get_game_status(game_hash: EntryHashB64) -> ExternResult<$crate::GameStatus> {
+            $crate::get_game_status::<$turn_based_game>(game_hash)
+        }
+
+        #[hdk_extern]
+        fn get_game_result(game_hash: EntryHashB64) -> ExternResult<Option<$crate::GameOutcome>> {
+            $crate::get_game_result::<$turn_based_game>(game_hash)
+        }
+
+        #[hdk_extern]
+        fn get_my_game_history(
+            filter: $crate::GameHistoryFilter,
+        ) -> ExternResult<Vec<$crate::GameResultInfo>> {
+            $crate::get_my_game_history(filter)
+        }
+
+        #[hdk_extern]
+        fn create_match(
+            input: $crate::CreateMatchInput<$turn_based_game>,
+        ) -> ExternResult<EntryHashB64> {
+            $crate::create_match::<$turn_based_game>(
+                input.teams,
+                input.best_of,
+                input.settings,
+                input.options,
+            )
+        }
+
+        #[hdk_extern]
+        fn get_match_state(match_hash: EntryHashB64) -> ExternResult<$crate::MatchState> {
+            $crate::get_match_state(match_hash)
+        }
+
+        #[hdk_extern]
+        fn create_tournament(
+            input: $crate::CreateTournamentInput<$turn_based_game>,
+        ) -> ExternResult<EntryHashB64> {
+            $crate::create_tournament::<$turn_based_game>(
+                input.name,
+                input.format,
+                input.settings,
+                input.options,
+            )
         }
 
         #[hdk_extern]
-        fn validate_create_entry_game_move_entry(
-            validate_data: ValidateData,
-        ) -> ExternResult<ValidateCallbackResult> {
-            $crate::validate_game_move_entry::<$turn_based_game>(validate_data)
+        fn get_tournaments(_: ()) -> ExternResult<Vec<$crate::TournamentInfo>> {
+            $crate::get_tournaments()
         }
 
         #[hdk_extern]
-        fn validate_update_entry_game_move_entry(
-            validate_data: ValidateData,
-        ) -> ExternResult<ValidateCallbackResult> {
-            Ok(ValidateCallbackResult::Invalid(
-                "Cannot update game moves".into(),
-            ))
+        fn register_for_tournament(tournament_hash: EntryHashB64) -> ExternResult<()> {
+            $crate::register_for_tournament(tournament_hash)
         }
 
         #[hdk_extern]
-        fn validate_delete_entry_game_entry(
-            validate_data: ValidateData,
-        ) -> ExternResult<ValidateCallbackResult> {
-            Ok(ValidateCallbackResult::Invalid(
-                "Cannot delete games".into(),
-            ))
+        fn start_next_tournament_round(
+            tournament_hash: EntryHashB64,
+        ) -> ExternResult<EntryHashB64> {
+            $crate::start_next_tournament_round(tournament_hash)
         }
 
         #[hdk_extern]
-        fn validate_update_entry_game_entry(
-            validate_data: ValidateData,
-        ) -> ExternResult<ValidateCallbackResult> {
-            Ok(ValidateCallbackResult::Invalid(
-                "Cannot update games".into(),
-            ))
-        }*/
+        fn get_tournament_state(
+            tournament_hash: EntryHashB64,
+        ) -> ExternResult<$crate::TournamentState> {
+            $crate::get_tournament_state(tournament_hash)
+        }
+
+        #[hdk_extern]
+        fn offer_rematch(input: $crate::OfferRematchInput) -> ExternResult<()> {
+            $crate::offer_rematch::<$turn_based_game>(input.game_hash, input.swap_seats)
+        }
+
+        #[hdk_extern]
+        fn accept_rematch(game_hash: EntryHashB64) -> ExternResult<EntryHashB64> {
+            $crate::accept_rematch::<$turn_based_game>(game_hash)
+        }
+
+        #[hdk_extern]
+        fn get_rematch(game_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+            $crate::get_rematch(game_hash)
+        }
+
+        #[hdk_extern]
+        fn send_invitation(input: $crate::SendInvitationInput) -> ExternResult<EntryHashB64> {
+            $crate::send_invitation(input.players, input.settings)
+        }
+
+        #[hdk_extern]
+        fn accept_invitation(invitation_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+            $crate::accept_invitation(invitation_hash)
+        }
+
+        #[hdk_extern]
+        fn start_invited_game(invitation_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+            $crate::start_invited_game(invitation_hash)
+        }
+
+        #[hdk_extern]
+        fn reject_invitation(invitation_hash: EntryHashB64) -> ExternResult<()> {
+            $crate::reject_invitation(invitation_hash)
+        }
+
+        #[hdk_extern]
+        fn get_my_pending_invitations(_: ()) -> ExternResult<Vec<$crate::InvitationInfo>> {
+            $crate::get_my_pending_invitations()
+        }
+
+        #[hdk_extern]
+        fn publish_game_request(filter: $crate::GameRequestFilter) -> ExternResult<EntryHashB64> {
+            $crate::publish_game_request::<$turn_based_game>(filter)
+        }
+
+        #[hdk_extern]
+        fn get_open_game_requests(
+            filter: $crate::GameRequestFilter,
+        ) -> ExternResult<Vec<$crate::GameRequestInfo>> {
+            $crate::get_open_game_requests(filter)
+        }
+
+        #[hdk_extern]
+        fn join_game_request(
+            game_request_hash: EntryHashB64,
+        ) -> ExternResult<Option<EntryHashB64>> {
+            $crate::join_game_request(game_request_hash)
+        }
+
+        #[hdk_extern]
+        fn start_requested_game(
+            game_request_hash: EntryHashB64,
+        ) -> ExternResult<Option<EntryHashB64>> {
+            $crate::start_requested_game(game_request_hash)
+        }
+
+        #[hdk_extern]
+        fn cancel_game_request(game_request_hash: EntryHashB64) -> ExternResult<()> {
+            $crate::cancel_game_request(game_request_hash)
+        }
+
+        #[hdk_extern]
+        fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+            $crate::validate_turn_based_game_op::<$turn_based_game>(op)
+        }
+    };
+}
+
+/**
+ * Optional externs to rate the players of the finished games
+ *
+ * Requires `RatingChangeEntry` to be registered in the `entry_defs` of the zome
+ */
+#[macro_export]
+macro_rules! mixin_ratings {
+    () => {
+        #[hdk_extern]
+        fn update_ratings(game_hash: EntryHashB64) -> ExternResult<EntryHashB64> {
+            $crate::update_ratings(game_hash)
+        }
+
+        #[hdk_extern]
+        fn get_rating(agent: hdk::prelude::holo_hash::AgentPubKeyB64) -> ExternResult<f64> {
+            $crate::get_rating(agent)
+        }
+
+        #[hdk_extern]
+        fn get_leaderboard(_: ()) -> ExternResult<Vec<$crate::LeaderboardEntry>> {
+            $crate::get_leaderboard()
+        }
     };
 }
@@ -0,0 +1,569 @@
+use hdk::hdk::MockHdkT;
+use hdk::prelude::holo_hash::hash_type::{self, AnyDht};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    current_games::remove_my_current_game, signal::SignalPayload, simulated_players, GameEntry,
+    GameMoveEntry, GameRequest, GameResultEntry, Invitation, MatchEntry, RatingChangeEntry,
+    TournamentEntry, TournamentRound,
+};
+
+/**
+ * Function of the zome that other agents can call remotely, run as the agent that receives the call
+ */
+pub type RemoteHandler = Arc<dyn Fn(ExternIO) -> ExternResult<ExternIO> + Send + Sync>;
+
+/**
+ * In-memory network of agents running the mixin, that replaces the host functions of the HDK
+ * so that the zome functions can be tested in a plain `cargo test`, without a conductor
+ *
+ * Entries, links and signals are shared by all the agents, who can be taken offline to test
+ * what happens when they can't receive remote calls or signals
+ *
+ * Validation callbacks are not run: the rules of the game can be tested with the `GameSimulator`
+ */
+#[derive(Clone)]
+pub struct MockNetwork {
+    agents: Vec<AgentPubKeyB64>,
+    state: Arc<Mutex<NetworkState>>,
+    remote_handlers: Arc<Mutex<HashMap<String, RemoteHandler>>>,
+}
+
+impl MockNetwork {
+    /**
+     * Creates a network with the given number of agents and installs it as the HDK of the current thread
+     *
+     * The zome functions are called as the first agent, until `as_agent` is used
+     */
+    pub fn new(agents_count: u8) -> Self {
+        let agents = simulated_players(agents_count);
+
+        let network = MockNetwork {
+            state: Arc::new(Mutex::new(NetworkState::new(
+                agents
+                    .first()
+                    .cloned()
+                    .expect("The network needs at least one agent")
+                    .into(),
+            ))),
+            agents,
+            remote_handlers: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        network.on_remote_call("notify_remove_my_current_game", |payload| {
+            let game_hash: EntryHashB64 = payload.decode()?;
+            remove_my_current_game(game_hash.into())?;
+            Ok(ExternIO::encode(())?)
+        });
+
+        set_hdk(network.mock_hdk());
+
+        network
+    }
+
+    /**
+     * Agents of the network
+     */
+    pub fn agents(&self) -> Vec<AgentPubKeyB64> {
+        self.agents.clone()
+    }
+
+    /**
+     * Runs the given function as the given agent, restoring the previous agent afterwards
+     */
+    pub fn as_agent<R>(&self, agent: &AgentPubKeyB64, f: impl FnOnce() -> R) -> R {
+        let previous_agent = std::mem::replace(
+            &mut self.lock().current_agent,
+            AgentPubKey::from(agent.clone()),
+        );
+
+        let result = f();
+
+        self.lock().current_agent = previous_agent;
+
+        result
+    }
+
+    /**
+     * Takes the agent offline or back online
+     *
+     * Offline agents don't receive remote calls nor remote signals
+     */
+    pub fn set_online(&self, agent: &AgentPubKeyB64, online: bool) {
+        let agent = AgentPubKey::from(agent.clone());
+        let mut state = self.lock();
+
+        state.offline_agents.retain(|a| !a.eq(&agent));
+        if !online {
+            state.offline_agents.push(agent);
+        }
+    }
+
+    /**
+     * Moves the time of the network forward
+     */
+    pub fn advance_time(&self, duration: chrono::Duration) {
+        self.lock().now_micros += duration.num_microseconds().unwrap_or(i64::MAX);
+    }
+
+    /**
+     * Registers the function that runs when another agent calls the given function remotely
+     */
+    pub fn on_remote_call(
+        &self,
+        fn_name: &str,
+        handler: impl Fn(ExternIO) -> ExternResult<ExternIO> + Send + Sync + 'static,
+    ) {
+        self.remote_handlers
+            .lock()
+            .unwrap()
+            .insert(fn_name.to_string(), Arc::new(handler));
+    }
+
+    /**
+     * Names of the functions that other agents have tried to call remotely on the given agent,
+     * including the calls that failed because the agent was offline
+     */
+    pub fn remote_calls_to(&self, agent: &AgentPubKeyB64) -> Vec<String> {
+        let agent = AgentPubKey::from(agent.clone());
+
+        self.lock()
+            .remote_calls
+            .iter()
+            .filter(|(to, _)| to.eq(&agent))
+            .map(|(_, fn_name)| fn_name.clone())
+            .collect()
+    }
+
+    /**
+     * Remote signals that the given agent has received, as `recv_remote_signal` would decode them
+     */
+    pub fn signals_received_by(&self, agent: &AgentPubKeyB64) -> Vec<SignalPayload> {
+        let agent = AgentPubKey::from(agent.clone());
+
+        self.lock()
+            .remote_signals
+            .iter()
+            .filter(|(to, _)| to.eq(&agent))
+            .map(|(_, signal)| {
+                let signal: ExternIO = signal.decode().expect("Couldn't decode remote signal");
+                signal.decode().expect("Couldn't decode signal payload")
+            })
+            .collect()
+    }
+
+    /**
+     * Signals that the given agent has emitted to their UI
+     */
+    pub fn signals_emitted_by(&self, agent: &AgentPubKeyB64) -> Vec<SignalPayload> {
+        let agent = AgentPubKey::from(agent.clone());
+
+        self.lock()
+            .emitted_signals
+            .iter()
+            .filter(|(from, _)| from.eq(&agent))
+            .map(|(_, signal)| signal.decode().expect("Couldn't decode signal payload"))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<NetworkState> {
+        self.state.lock().unwrap()
+    }
+
+    /**
+     * Builds the mock HDK, answering each host function from the state of the network
+     *
+     * The zome is defined with the entries of the mixin, so the entries get the type that the validation expects
+     *
+     * Only entries and sha256 can be hashed, and the rest of host functions, which the mixin doesn't call,
+     * are left without expectations, so calling them panics
+     */
+    fn mock_hdk(&self) -> MockHdkT {
+        let mut mock_hdk = MockHdkT::new();
+
+        let network = self.clone();
+        mock_hdk
+            .expect_agent_info()
+            .returning(move |_| Ok(network.lock().agent_info()));
+
+        mock_hdk.expect_zome_info().returning(|_| {
+            Ok(ZomeInfo {
+                name: "turn_based_game".into(),
+                id: ZomeId::from(0),
+                properties: SerializedBytes::from(UnsafeBytes::from(vec![])),
+                entry_defs: mixin_entry_defs().into(),
+                extern_fns: vec![],
+            })
+        });
+
+        let network = self.clone();
+        mock_hdk
+            .expect_sys_time()
+            .returning(move |_| Ok(network.lock().now()));
+
+        mock_hdk.expect_hash().returning(|input| match input {
+            HashInput::Entry(entry) => Ok(HashOutput::Entry(hash_of_entry(&entry)?)),
+            HashInput::Sha256(bytes) => {
+                let hash: [u8; 32] = Sha256::digest(&bytes).into();
+                Ok(HashOutput::Sha256(hash.into()))
+            }
+            _ => Err(WasmError::Guest(
+                "The mock HDK can only hash entries and sha256".into(),
+            )),
+        });
+
+        let network = self.clone();
+        mock_hdk.expect_create().returning(move |input| {
+            network
+                .lock()
+                .create_entry(&input.entry_def_id, input.entry)
+        });
+
+        let network = self.clone();
+        mock_hdk.expect_get().returning(move |inputs| {
+            let state = network.lock();
+
+            Ok(inputs
+                .into_iter()
+                .map(|input| state.get_element(input.any_dht_hash))
+                .collect())
+        });
+
+        let network = self.clone();
+        mock_hdk.expect_must_get_entry().returning(move |input| {
+            let entry_hash = input.into_inner();
+
+            network
+                .lock()
+                .get_element(entry_hash.clone().into())
+                .and_then(|element| element.into_inner().1.into_option())
+                .map(|entry| EntryHashed::with_pre_hashed(entry, entry_hash))
+                .ok_or(WasmError::Guest("Entry not found".into()))
+        });
+
+        let network = self.clone();
+        mock_hdk
+            .expect_must_get_valid_element()
+            .returning(move |input| {
+                network
+                    .lock()
+                    .get_element(input.into_inner().into())
+                    .ok_or(WasmError::Guest("Element not found".into()))
+            });
+
+        let network = self.clone();
+        mock_hdk.expect_create_link().returning(move |input| {
+            Ok(network
+                .lock()
+                .create_link(input.base_address, input.target_address, input.tag))
+        });
+
+        let network = self.clone();
+        mock_hdk.expect_get_links().returning(move |inputs| {
+            let state = network.lock();
+
+            Ok(inputs
+                .into_iter()
+                .map(|input| state.get_links(&input.base_address, input.tag_prefix))
+                .collect())
+        });
+
+        let network = self.clone();
+        mock_hdk
+            .expect_delete_link()
+            .returning(move |input| network.lock().delete_link(input.address));
+
+        let network = self.clone();
+        mock_hdk
+            .expect_remote_signal()
+            .returning(move |remote_signal| {
+                let mut state = network.lock();
+
+                for agent in remote_signal.agents {
+                    if state.is_online(&agent) {
+                        state
+                            .remote_signals
+                            .push((agent, remote_signal.signal.clone()));
+                    }
+                }
+
+                Ok(())
+            });
+
+        let network = self.clone();
+        mock_hdk.expect_emit_signal().returning(move |app_signal| {
+            let mut state = network.lock();
+            let agent = state.current_agent.clone();
+
+            state.emitted_signals.push((agent, app_signal.into_inner()));
+
+            Ok(())
+        });
+
+        let network = self.clone();
+        mock_hdk.expect_call().returning(move |calls| {
+            Ok(calls
+                .into_iter()
+                .map(|call| network.receive_call(call))
+                .collect())
+        });
+
+        mock_hdk
+    }
+
+    /**
+     * Runs the remote call as the agent that receives it, or fails with a network error if they are offline
+     */
+    fn receive_call(&self, call: Call) -> ZomeCallResponse {
+        let agent = match call.target {
+            CallTarget::NetworkAgent(agent) => agent,
+            _ => {
+                return ZomeCallResponse::NetworkError(
+                    "The mock HDK can only call other agents".into(),
+                )
+            }
+        };
+        let fn_name = call.fn_name.0;
+
+        let online = {
+            let mut state = self.lock();
+            state.remote_calls.push((agent.clone(), fn_name.clone()));
+            state.is_online(&agent)
+        };
+
+        if !online {
+            return ZomeCallResponse::NetworkError(format!("Agent {:?} is offline", agent));
+        }
+
+        let maybe_handler = self.remote_handlers.lock().unwrap().get(&fn_name).cloned();
+
+        match maybe_handler {
+            None => ZomeCallResponse::NetworkError(format!(
+                "There is no handler for the remote call {}",
+                fn_name
+            )),
+            Some(handler) => match self.as_agent(&agent.into(), || handler(call.payload)) {
+                Ok(result) => ZomeCallResponse::Ok(result),
+                Err(error) => ZomeCallResponse::NetworkError(format!("{:?}", error)),
+            },
+        }
+    }
+}
+
+/**
+ * Shared state of the network, as if every agent could see every entry and link immediately
+ */
+struct NetworkState {
+    current_agent: AgentPubKey,
+    offline_agents: Vec<AgentPubKey>,
+    now_micros: i64,
+    headers_count: u32,
+    elements: HashMap<HeaderHash, Element>,
+    entry_headers: HashMap<EntryHash, HeaderHash>,
+    links: Vec<MockLink>,
+    remote_signals: Vec<(AgentPubKey, ExternIO)>,
+    emitted_signals: Vec<(AgentPubKey, ExternIO)>,
+    remote_calls: Vec<(AgentPubKey, String)>,
+}
+
+struct MockLink {
+    base: EntryHash,
+    link: Link,
+    deleted: bool,
+}
+
+impl NetworkState {
+    fn new(current_agent: AgentPubKey) -> Self {
+        NetworkState {
+            current_agent,
+            offline_agents: vec![],
+            // 2022-01-01T00:00:00Z
+            now_micros: 1_640_995_200_000_000,
+            headers_count: 0,
+            elements: HashMap::new(),
+            entry_headers: HashMap::new(),
+            links: vec![],
+            remote_signals: vec![],
+            emitted_signals: vec![],
+            remote_calls: vec![],
+        }
+    }
+
+    fn now(&self) -> Timestamp {
+        Timestamp::from_micros(self.now_micros)
+    }
+
+    fn is_online(&self, agent: &AgentPubKey) -> bool {
+        !self.offline_agents.contains(agent)
+    }
+
+    fn agent_info(&self) -> AgentInfo {
+        AgentInfo {
+            agent_initial_pubkey: self.current_agent.clone(),
+            agent_latest_pubkey: self.current_agent.clone(),
+            chain_head: (
+                HeaderHash::from_raw_32(fake_hash_bytes(self.headers_count)),
+                self.headers_count,
+                self.now(),
+            ),
+        }
+    }
+
+    fn next_header_hash(&mut self) -> HeaderHash {
+        self.headers_count += 1;
+        HeaderHash::from_raw_32(fake_hash_bytes(self.headers_count))
+    }
+
+    fn create_entry(
+        &mut self,
+        entry_def_id: &EntryDefId,
+        entry: Entry,
+    ) -> ExternResult<HeaderHash> {
+        let entry_hash = hash_of_entry(&entry)?;
+        let header_hash = self.next_header_hash();
+
+        let header = Header::Create(Create {
+            author: self.current_agent.clone(),
+            timestamp: self.now(),
+            header_seq: self.headers_count,
+            prev_header: HeaderHash::from_raw_32(fake_hash_bytes(self.headers_count - 1)),
+            entry_type: EntryType::App(AppEntryType::new(
+                entry_def_index(entry_def_id),
+                ZomeId::from(0),
+                EntryVisibility::Public,
+            )),
+            entry_hash: entry_hash.clone(),
+        });
+
+        let signed_header = SignedHeaderHashed::with_presigned(
+            HeaderHashed::with_pre_hashed(header, header_hash.clone()),
+            Signature([0; 64]),
+        );
+
+        self.elements.insert(
+            header_hash.clone(),
+            Element::new(signed_header, Some(entry)),
+        );
+        self.entry_headers
+            .entry(entry_hash)
+            .or_insert(header_hash.clone());
+
+        Ok(header_hash)
+    }
+
+    fn get_element(&self, hash: AnyDhtHash) -> Option<Element> {
+        let header_hash = match hash.hash_type() {
+            AnyDht::Entry => self
+                .entry_headers
+                .get(&hash.retype(hash_type::Entry))?
+                .clone(),
+            AnyDht::Header => hash.retype(hash_type::Header),
+        };
+
+        self.elements.get(&header_hash).cloned()
+    }
+
+    fn create_link(&mut self, base: EntryHash, target: EntryHash, tag: LinkTag) -> HeaderHash {
+        let create_link_hash = self.next_header_hash();
+
+        self.links.push(MockLink {
+            base,
+            link: Link {
+                target,
+                timestamp: self.now(),
+                tag,
+                create_link_hash: create_link_hash.clone(),
+            },
+            deleted: false,
+        });
+
+        create_link_hash
+    }
+
+    fn get_links(&self, base: &EntryHash, tag_prefix: Option<LinkTag>) -> Vec<Link> {
+        self.links
+            .iter()
+            .filter(|mock_link| !mock_link.deleted && mock_link.base.eq(base))
+            .filter(|mock_link| match &tag_prefix {
+                None => true,
+                Some(prefix) => mock_link.link.tag.0.starts_with(&prefix.0),
+            })
+            .map(|mock_link| mock_link.link.clone())
+            .collect()
+    }
+
+    fn delete_link(&mut self, create_link_hash: HeaderHash) -> ExternResult<HeaderHash> {
+        let mock_link = self
+            .links
+            .iter_mut()
+            .find(|mock_link| mock_link.link.create_link_hash.eq(&create_link_hash))
+            .ok_or(WasmError::Guest("There is no link to delete".into()))?;
+
+        mock_link.deleted = true;
+
+        Ok(self.next_header_hash())
+    }
+}
+
+/**
+ * Entry definitions of a zome that only defines the entries of the mixin, in the order of the README
+ */
+fn mixin_entry_defs() -> Vec<EntryDef> {
+    vec![
+        GameMoveEntry::entry_def(),
+        GameEntry::entry_def(),
+        MatchEntry::entry_def(),
+        TournamentEntry::entry_def(),
+        TournamentRound::entry_def(),
+        GameResultEntry::entry_def(),
+        Invitation::entry_def(),
+        GameRequest::entry_def(),
+        RatingChangeEntry::entry_def(),
+        Path::entry_def(),
+    ]
+}
+
+/**
+ * Index of the entry definition with the given id in the zome,
+ * or an index after all of them for the entries that the zome doesn't define
+ */
+fn entry_def_index(entry_def_id: &EntryDefId) -> EntryDefIndex {
+    let entry_defs = mixin_entry_defs();
+
+    let index = entry_defs
+        .iter()
+        .position(|entry_def| entry_def.id.eq(entry_def_id))
+        .unwrap_or(entry_defs.len());
+
+    EntryDefIndex::from(index as u8)
+}
+
+/**
+ * Deterministic hash of the contents of the entry, so that equal entries get the same hash as in the DHT
+ */
+fn hash_of_entry(entry: &Entry) -> ExternResult<EntryHash> {
+    let bytes = ExternIO::encode(entry)?.0;
+
+    let hash_bytes: Vec<u8> = (0..4u64)
+        .flat_map(|seed| {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            bytes.hash(&mut hasher);
+            hasher.finish().to_le_bytes().to_vec()
+        })
+        .collect();
+
+    Ok(EntryHash::from_raw_32(hash_bytes))
+}
+
+fn fake_hash_bytes(index: u32) -> Vec<u8> {
+    let mut bytes = index.to_le_bytes().to_vec();
+    bytes.resize(32, 0xff);
+    bytes
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_move::{GameMoveEntry, MoveType},
+    random_beacon::RandomBeacon,
+    rounds::SimultaneousRound,
+};
+
+/**
+ * State of the protocols of the mixin that span several moves, in the moment a move is made
+ *
+ * Every move stores the context in which it was made, which is validated against the context of the previous move,
+ * so that it doesn't need to be rebuilt from all the moves of the game
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MoveContext {
+    pub(crate) random_beacon: RandomBeacon,
+    pub(crate) round: SimultaneousRound,
+    // Last effective move defined by the game, and its author
+    pub(crate) last_game_move_hash: Option<HeaderHashB64>,
+    pub(crate) last_game_mover: Option<AgentPubKeyB64>,
+    // Number of effective moves defined by the game
+    pub(crate) game_moves_count: u32,
+    // Commit move of each player that has not been revealed yet
+    pub(crate) open_commitments: BTreeMap<AgentPubKeyB64, HeaderHashB64>,
+    // Number of game moves requested to be taken back, if the previous move is a takeback request
+    pub(crate) requested_takeback: Option<u32>,
+    // Moment of the last move defined by the game, including the reverted ones
+    pub(crate) last_game_move_at: Option<Timestamp>,
+    // Moment of the last move of each player
+    pub(crate) last_move_at: BTreeMap<AgentPubKeyB64, Timestamp>,
+}
+
+impl MoveContext {
+    /**
+     * Updates the protocols that affect the state of the game, the random beacon and the simultaneous round, with the given move
+     *
+     * Doesn't call any host function, so that the state of the game can be built outside of a conductor
+     */
+    pub(crate) fn observe(
+        &mut self,
+        players: &Vec<AgentPubKeyB64>,
+        game_move: &GameMoveEntry,
+    ) -> ExternResult<()> {
+        self.random_beacon.observe(players, game_move)?;
+        self.round.observe(players, game_move);
+
+        Ok(())
+    }
+
+    /**
+     * Builds the context for the move that follows the given one, which was made in this context
+     *
+     * Accepting a takeback restores the context of the first reverted game move, so it's the only move that needs
+     * to get other moves: the game moves that it reverts, which are got with the given function
+     */
+    pub(crate) fn following(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        move_hash: &HeaderHashB64,
+        timestamp: Timestamp,
+        game_move: &GameMoveEntry,
+        get_move: &dyn Fn(HeaderHashB64) -> ExternResult<GameMoveEntry>,
+    ) -> ExternResult<MoveContext> {
+        let mut context = match &game_move.game_move {
+            MoveType::AcceptTakeback => {
+                let first_reverted = first_reverted_game_move(self, get_move)?;
+
+                // The random beacon is not reverted: its seed was used by the reverted game moves,
+                // so restoring it would let the players replay them with the same randomness
+                MoveContext {
+                    random_beacon: self.random_beacon.clone(),
+                    requested_takeback: None,
+                    last_game_move_at: self.last_game_move_at,
+                    last_move_at: self.last_move_at.clone(),
+                    ..first_reverted.context
+                }
+            }
+            move_type => {
+                let mut context = self.clone();
+                context.observe(players, game_move)?;
+
+                if move_type.is_game_move() {
+                    context.last_game_move_hash = Some(move_hash.clone());
+                    context.last_game_mover = Some(game_move.author_pub_key.clone());
+                    context.game_moves_count += 1;
+                    context.last_game_move_at = Some(timestamp);
+                }
+
+                match move_type {
+                    MoveType::CommitMove { .. } => {
+                        context
+                            .open_commitments
+                            .insert(game_move.author_pub_key.clone(), move_hash.clone());
+                    }
+                    MoveType::RevealMove { .. } => {
+                        context.open_commitments.remove(&game_move.author_pub_key);
+                    }
+                    _ => {}
+                }
+
+                context.requested_takeback = match move_type {
+                    MoveType::RequestTakeback { moves } => Some(*moves),
+                    _ => None,
+                };
+
+                context
+            }
+        };
+
+        context
+            .last_move_at
+            .insert(game_move.author_pub_key.clone(), timestamp);
+
+        Ok(context)
+    }
+}
+
+/** Helper functions */
+
+/**
+ * Builds the context for the move that follows the given last move of the game
+ */
+pub(crate) fn next_move_context(
+    players: &Vec<AgentPubKeyB64>,
+    last_move: Option<&(HeaderHashB64, GameMoveEntry)>,
+) -> ExternResult<MoveContext> {
+    match last_move {
+        None => Ok(MoveContext::default()),
+        Some((last_move_hash, last_move)) => {
+            let element = must_get_valid_element(last_move_hash.clone().into())?;
+
+            last_move.context.following(
+                players,
+                last_move_hash,
+                element.header().timestamp(),
+                last_move,
+                &get_move,
+            )
+        }
+    }
+}
+
+/**
+ * Returns the first of the game moves that the takeback requested in the given context reverts,
+ * walking back only through the effective game moves, which are got with the given function
+ */
+pub(crate) fn first_reverted_game_move(
+    context: &MoveContext,
+    get_move: &dyn Fn(HeaderHashB64) -> ExternResult<GameMoveEntry>,
+) -> ExternResult<GameMoveEntry> {
+    let requested_moves = context.requested_takeback.ok_or(WasmError::Guest(
+        "There is no takeback request to accept".into(),
+    ))?;
+
+    let mut maybe_game_move_hash = context.last_game_move_hash.clone();
+    let mut first_reverted = None;
+
+    for _ in 0..requested_moves {
+        let game_move_hash = maybe_game_move_hash.ok_or(WasmError::Guest(
+            "There are not enough moves to take back".into(),
+        ))?;
+
+        let game_move = get_move(game_move_hash)?;
+
+        maybe_game_move_hash = game_move.context.last_game_move_hash.clone();
+        first_reverted = Some(game_move);
+    }
+
+    first_reverted.ok_or(WasmError::Guest("There are no moves to take back".into()))
+}
+
+/**
+ * Gets the move with the given hash, which must have been validated
+ */
+pub(crate) fn get_move(move_hash: HeaderHashB64) -> ExternResult<GameMoveEntry> {
+    must_get_valid_element(move_hash.into())?
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("Couldn't deserialize move".into()))
+}
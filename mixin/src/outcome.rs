@@ -0,0 +1,108 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    game::{get_current_game_state, get_game, GameEntry},
+    game_move::{get_last_move, GameMoveEntry, MoveType},
+    turn_based_game::{GameOutcome, OutcomeReason, TurnBasedGame},
+};
+
+/** Public handlers */
+
+/**
+ * Gets the outcome of the game, or None if it has not finished yet
+ */
+pub fn get_game_result<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+) -> ExternResult<Option<GameOutcome>> {
+    let last_move = get_last_move(game_hash.clone())?;
+    let game = get_game(game_hash.clone())?;
+    let last_move_entry = last_move.as_ref().map(|(_, m)| m);
+
+    let game_state = get_current_game_state::<G>(game_hash, &game, last_move_entry)?;
+
+    Ok(game_outcome(&game, &game_state, last_move_entry))
+}
+
+/** Helper functions */
+
+/**
+ * Returns the outcome of the game given its state and its last move
+ *
+ * Moves handled by the mixin take precedence over the rules of the game
+ */
+pub(crate) fn game_outcome<G: TurnBasedGame>(
+    game: &GameEntry,
+    game_state: &G,
+    last_move: Option<&GameMoveEntry>,
+) -> Option<GameOutcome> {
+    last_move
+        .and_then(|last_move| {
+            mixin_move_outcome(game, &last_move.author_pub_key, &last_move.game_move)
+        })
+        .or_else(|| game_state.outcome(&game.players))
+}
+
+/**
+ * Returns the outcome of the game if the given move handled by the mixin finishes it
+ */
+pub(crate) fn mixin_move_outcome(
+    game: &GameEntry,
+    author: &AgentPubKeyB64,
+    move_type: &MoveType,
+) -> Option<GameOutcome> {
+    let rivals: Vec<AgentPubKeyB64> = game
+        .players
+        .iter()
+        .filter(|p| !p.eq(&author))
+        .cloned()
+        .collect();
+
+    match move_type {
+        MoveType::Resign => Some(GameOutcome::victory(
+            rivals,
+            &game.players,
+            OutcomeReason::Resignation,
+        )),
+        MoveType::AcceptDraw => Some(GameOutcome::draw(&game.players, OutcomeReason::Agreement)),
+        MoveType::ClaimTimeoutVictory => Some(GameOutcome::victory(
+            vec![author.clone()],
+            &game.players,
+            OutcomeReason::Timeout,
+        )),
+        MoveType::ClaimForfeit => Some(GameOutcome::victory(
+            vec![author.clone()],
+            &game.players,
+            OutcomeReason::Abandonment,
+        )),
+        _ => None,
+    }
+}
+
+/**
+ * Returns the outcome of the game after the given move, verifying that it's a valid move of the game
+ */
+pub(crate) fn final_outcome<G: TurnBasedGame>(
+    game: &GameEntry,
+    game_hash: &EntryHashB64,
+    last_move_hash: &HeaderHashB64,
+) -> ExternResult<Option<GameOutcome>> {
+    let move_element = must_get_valid_element(last_move_hash.clone().into())?;
+
+    let last_move: GameMoveEntry = move_element
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("The last move is not a game move".into()))?;
+
+    if !last_move.game_hash.eq(game_hash) {
+        return Err(WasmError::Guest(
+            "The last move is not a move of this game".into(),
+        ));
+    }
+
+    let game_state = G::try_from(last_move.resulting_game_state.clone()).or(Err(
+        WasmError::Guest("Couldn't deserialize game state".into()),
+    ))?;
+
+    Ok(game_outcome(game, &game_state, Some(&last_move)))
+}
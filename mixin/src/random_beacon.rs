@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    commit_reveal::sha256,
+    game_move::{commit_move, GameMoveEntry, MoveType},
+    turn_based_game::TurnBasedGame,
+};
+
+/**
+ * Deterministic random number generator, seeded from the secrets of all players
+ *
+ * Every validator gets the same numbers for the same move, so dice rolls and shuffles can be reproduced
+ */
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let state = seed
+            .iter()
+            .take(8)
+            .fold(0u64, |state, byte| (state << 8) | *byte as u64);
+
+        GameRng { state }
+    }
+
+    /**
+     * Returns the next random number, following the SplitMix64 algorithm
+     */
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /**
+     * Returns a random number between 0 (inclusive) and upper (exclusive), without bias
+     */
+    pub fn gen_range(&mut self, upper: u64) -> u64 {
+        if upper == 0 {
+            return 0;
+        }
+
+        let zone = u64::MAX - (u64::MAX % upper);
+
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % upper;
+            }
+        }
+    }
+
+    /**
+     * Rolls a die with the given number of sides, returning a number between 1 and sides
+     */
+    pub fn roll_die(&mut self, sides: u64) -> u64 {
+        self.gen_range(sides) + 1
+    }
+
+    /**
+     * Shuffles the items in place, following the Fisher-Yates algorithm
+     */
+    pub fn shuffle<T>(&mut self, items: &mut Vec<T>) {
+        for i in (1..items.len()).rev() {
+            let j = self.gen_range(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/**
+ * State of the random beacon protocol, carried from move to move in their context
+ *
+ * In each round, all players commit to a secret and then reveal it; once all secrets are revealed,
+ * they are combined into the seed used for the randomness of the next game move
+ *
+ * Each seed is used by a single game move, so every draw needs a new round: otherwise, once the seed is known,
+ * anyone could predict the randomness of all the moves that follow
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RandomBeacon {
+    // Commitments of the current round
+    commitments: BTreeMap<AgentPubKeyB64, Vec<u8>>,
+    // Secrets revealed in the current round
+    secrets: BTreeMap<AgentPubKeyB64, Vec<u8>>,
+    // Seed of the last completed round, until a game move uses it
+    seed: Option<Vec<u8>>,
+}
+
+/** Public handlers */
+
+/**
+ * Commits to the given secret for the current round of the random beacon
+ *
+ * The secret must be kept until all players have committed, and then revealed with `reveal_seed`
+ */
+pub fn commit_seed<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    secret: Vec<u8>,
+) -> ExternResult<HeaderHashB64> {
+    let commitment = sha256(secret)?;
+
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::CommitSeed { commitment },
+    )
+}
+
+/**
+ * Reveals the secret that we committed to in the current round of the random beacon
+ *
+ * Only valid once all players have committed to their secrets
+ */
+pub fn reveal_seed<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    secret: Vec<u8>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::RevealSeed { secret },
+    )
+}
+
+/** Helper functions */
+
+impl RandomBeacon {
+    /**
+     * Updates the state of the random beacon with the given move
+     */
+    pub(crate) fn observe(
+        &mut self,
+        players: &Vec<AgentPubKeyB64>,
+        game_move: &GameMoveEntry,
+    ) -> ExternResult<()> {
+        match &game_move.game_move {
+            MoveType::CommitSeed { commitment } => {
+                self.commitments
+                    .insert(game_move.author_pub_key.clone(), commitment.clone());
+            }
+            MoveType::RevealSeed { secret } => {
+                self.secrets
+                    .insert(game_move.author_pub_key.clone(), secret.clone());
+
+                if players.iter().all(|p| self.secrets.contains_key(p)) {
+                    // Secrets are combined in the order of the players, so that the seed is deterministic
+                    let combined_secrets: Vec<u8> = players
+                        .iter()
+                        .filter_map(|p| self.secrets.get(p))
+                        .flatten()
+                        .cloned()
+                        .collect();
+
+                    self.seed = Some(sha256(combined_secrets)?);
+                    self.commitments.clear();
+                    self.secrets.clear();
+                }
+            }
+            game_move_type if game_move_type.is_game_move() => {
+                self.seed = None;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Random number generator for the next game move, or None if no round has been completed since the last game move
+     */
+    pub(crate) fn rng(&self) -> Option<GameRng> {
+        self.seed.as_ref().map(|seed| GameRng::from_seed(seed))
+    }
+
+    /**
+     * Returns error if the author can't commit to a secret in the current round
+     */
+    pub(crate) fn verify_can_commit(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        author: &AgentPubKeyB64,
+        commitment: &Vec<u8>,
+    ) -> ExternResult<()> {
+        if commitment.len() != 32 {
+            return Err(WasmError::Guest(
+                "The commitment must be the SHA-256 hash of the secret".into(),
+            ));
+        }
+        if self.commitments.contains_key(author) {
+            return Err(WasmError::Guest(
+                "Already committed to a secret in the current round of the random beacon".into(),
+            ));
+        }
+        if !players.contains(author) {
+            return Err(WasmError::Guest(
+                "Only the players of the game can take part in the random beacon".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Returns error if the author can't reveal the given secret in the current round
+     */
+    pub(crate) fn verify_can_reveal(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        author: &AgentPubKeyB64,
+        secret: &Vec<u8>,
+    ) -> ExternResult<()> {
+        if !players.iter().all(|p| self.commitments.contains_key(p)) {
+            return Err(WasmError::Guest(
+                "Cannot reveal the secret until all players have committed to theirs".into(),
+            ));
+        }
+        if self.secrets.contains_key(author) {
+            return Err(WasmError::Guest(
+                "Already revealed the secret in the current round of the random beacon".into(),
+            ));
+        }
+
+        match self.commitments.get(author) {
+            Some(commitment) if sha256(secret.clone())?.eq(commitment) => Ok(()),
+            _ => Err(WasmError::Guest(
+                "The revealed secret doesn't match the commitment".into(),
+            )),
+        }
+    }
+}
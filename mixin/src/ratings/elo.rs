@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::turn_based_game::GameOutcome;
+
+// Rating of the players that have not played any rated game yet
+pub const INITIAL_RATING: f64 = 1500.0;
+
+// Maximum rating change that a player can have in a single game
+pub const K_FACTOR: f64 = 32.0;
+
+/**
+ * Computes the new Elo ratings of the two players of a game, given their ratings before the game
+ */
+pub fn elo_ratings(
+    previous_ratings: &BTreeMap<AgentPubKeyB64, f64>,
+    outcome: &GameOutcome,
+) -> ExternResult<BTreeMap<AgentPubKeyB64, f64>> {
+    let players: Vec<(&AgentPubKeyB64, &f64)> = previous_ratings.iter().collect();
+
+    if players.len() != 2 {
+        return Err(WasmError::Guest(
+            "Elo ratings can only be computed for games with two players".into(),
+        ));
+    }
+
+    let (player_a, rating_a) = players[0];
+    let (player_b, rating_b) = players[1];
+
+    let score_a = player_score(outcome, player_a)?;
+    let score_b = player_score(outcome, player_b)?;
+
+    let expected_a = expected_score(*rating_a, *rating_b);
+    let expected_b = expected_score(*rating_b, *rating_a);
+
+    let mut new_ratings = BTreeMap::new();
+    new_ratings.insert(
+        player_a.clone(),
+        rating_a + K_FACTOR * (score_a - expected_a),
+    );
+    new_ratings.insert(
+        player_b.clone(),
+        rating_b + K_FACTOR * (score_b - expected_b),
+    );
+
+    Ok(new_ratings)
+}
+
+/**
+ * Probability of winning of a player with the given rating against a rival with the given rating
+ */
+fn expected_score(rating: f64, rival_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((rival_rating - rating) / 400.0))
+}
+
+fn player_score(outcome: &GameOutcome, player: &AgentPubKeyB64) -> ExternResult<f64> {
+    outcome
+        .scores
+        .get(player)
+        .cloned()
+        .ok_or(WasmError::Guest(format!(
+            "The outcome of the game has no score for player {}",
+            player
+        )))
+}
@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::convert::TryFrom;
+
+/**
+ * Change in the ratings of the players of a finished game
+ */
+#[hdk_entry(id = "rating_change_entry")]
+#[derive(Clone)]
+pub struct RatingChangeEntry {
+    pub game_hash: EntryHashB64,
+    pub last_move_hash: HeaderHashB64,
+    // Last rating change of each player before this game, None if it's their first rated game
+    pub previous_rating_changes: BTreeMap<AgentPubKeyB64, Option<HeaderHashB64>>,
+    pub new_ratings: BTreeMap<AgentPubKeyB64, f64>,
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LeaderboardEntry {
+    pub agent: AgentPubKeyB64,
+    pub rating: f64,
+}
@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::convert::TryFrom;
+
+use crate::game::get_game;
+use crate::game_result::get_game_result_entry;
+
+use super::{elo_ratings, LeaderboardEntry, RatingChangeEntry, INITIAL_RATING};
+
+/** Public handlers */
+
+/**
+ * Updates the ratings of the players of the given finished game
+ *
+ * Each game can only be rated once for each player, and its game result must have been committed already
+ * The previous rating of each player is the one from their last rated game that finished before this one,
+ * so all the players that rate the game at the same time commit the same entry
+ */
+pub fn update_ratings(game_hash: EntryHashB64) -> ExternResult<EntryHashB64> {
+    let game = get_game(game_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if game.seat_of(&my_pub_key).is_none() {
+        return Err(WasmError::Guest(
+            "Only the players of the game can update its ratings".into(),
+        ));
+    }
+
+    if get_links(
+        EntryHash::from(game_hash.clone()),
+        Some(rating_change_tag()),
+    )?
+    .len()
+        > 0
+    {
+        return Err(WasmError::Guest(
+            "The ratings for this game have already been updated".into(),
+        ));
+    }
+
+    for player in game.players.iter() {
+        if is_game_rated_for(player.clone(), &game_hash)? {
+            return Err(WasmError::Guest(format!(
+                "The rating of player {} has already been updated for this game",
+                player
+            )));
+        }
+    }
+
+    let game_result = get_game_result_entry(game_hash.clone())?
+        .ok_or(WasmError::Guest("The game has not finished yet".into()))?;
+
+    let finished_at = get(
+        HeaderHash::from(game_result.last_move_hash.clone()),
+        GetOptions::default(),
+    )?
+    .ok_or(WasmError::Guest("Couldn't get the last move".into()))?
+    .header()
+    .timestamp();
+
+    let mut previous_rating_changes = BTreeMap::new();
+    let mut previous_ratings = BTreeMap::new();
+
+    for player in game.players.iter() {
+        let latest_rating_change = get_latest_rating_change(player.clone(), Some(finished_at))?;
+
+        previous_ratings.insert(
+            player.clone(),
+            match latest_rating_change.as_ref() {
+                Some((_, rating_change)) => rating_of(rating_change, player)?,
+                None => INITIAL_RATING,
+            },
+        );
+        previous_rating_changes.insert(
+            player.clone(),
+            latest_rating_change.map(|(header_hash, _)| header_hash),
+        );
+    }
+
+    let rating_change = RatingChangeEntry {
+        game_hash: game_hash.clone(),
+        last_move_hash: game_result.last_move_hash,
+        new_ratings: elo_ratings(&previous_ratings, &game_result.outcome)?,
+        previous_rating_changes: previous_rating_changes.clone(),
+    };
+
+    create_entry(&rating_change)?;
+
+    let rating_change_hash = hash_entry(&rating_change)?;
+
+    create_link(
+        EntryHash::from(game_hash.clone()),
+        rating_change_hash.clone(),
+        LinkType(0),
+        rating_change_tag(),
+    )?;
+
+    let leaderboard_path = leaderboard_path();
+    leaderboard_path.ensure()?;
+
+    for (player, previous_rating_change) in previous_rating_changes {
+        let agent = AgentPubKey::from(player);
+
+        create_link(
+            agent.clone().into(),
+            rating_change_hash.clone(),
+            LinkType(0),
+            rating_changes_tag_with(RatingChangesTag {
+                finished_at,
+                game_hash: Some(game_hash.clone()),
+            })?,
+        )?;
+
+        // Players enter the leaderboard with their first rated game
+        if previous_rating_change.is_none() {
+            create_link(
+                leaderboard_path.hash()?,
+                agent.into(),
+                LinkType(0),
+                leaderboard_tag(),
+            )?;
+        }
+    }
+
+    Ok(rating_change_hash.into())
+}
+
+/**
+ * Gets the current rating of the given agent, which is the initial rating if they have not played any rated game
+ */
+pub fn get_rating(agent: AgentPubKeyB64) -> ExternResult<f64> {
+    match get_latest_rating_change(agent.clone(), None)? {
+        Some((_, rating_change)) => rating_of(&rating_change, &agent),
+        None => Ok(INITIAL_RATING),
+    }
+}
+
+/**
+ * Gets the current rating of all the agents that have played a rated game, from the highest to the lowest
+ */
+pub fn get_leaderboard() -> ExternResult<Vec<LeaderboardEntry>> {
+    let links = get_links(leaderboard_path().hash()?, Some(leaderboard_tag()))?;
+
+    let agents: BTreeSet<AgentPubKeyB64> = links
+        .into_iter()
+        .map(|link| link.target.retype(holo_hash::hash_type::Agent).into())
+        .collect();
+
+    let mut leaderboard = vec![];
+
+    for agent in agents {
+        leaderboard.push(LeaderboardEntry {
+            rating: get_rating(agent.clone())?,
+            agent,
+        });
+    }
+
+    leaderboard.sort_by(|a, b| {
+        b.rating
+            .partial_cmp(&a.rating)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(leaderboard)
+}
+
+/** Helper functions */
+
+pub(crate) fn rating_of(
+    rating_change: &RatingChangeEntry,
+    player: &AgentPubKeyB64,
+) -> ExternResult<f64> {
+    rating_change
+        .new_ratings
+        .get(player)
+        .cloned()
+        .ok_or(WasmError::Guest(
+            "The rating change does not include the player".into(),
+        ))
+}
+
+/** Private helpers */
+
+/**
+ * Gets the rating change of the last rated game of the given agent, along with the hash of its header,
+ * only considering the games that finished before the given moment if there is one
+ *
+ * Games are ordered by the moment in which they finished, stored in the tags of the links, but as tags are not validated
+ * the links are walked from the latest one until a rating change that includes the agent and really finished at the
+ * moment of its tag is found
+ */
+fn get_latest_rating_change(
+    agent: AgentPubKeyB64,
+    finished_before: Option<Timestamp>,
+) -> ExternResult<Option<(HeaderHashB64, RatingChangeEntry)>> {
+    let links = get_links(
+        AgentPubKey::from(agent.clone()).into(),
+        Some(rating_changes_tag()),
+    )?;
+
+    let mut candidates: Vec<(Timestamp, Link)> = links
+        .into_iter()
+        .filter_map(|link| parse_rating_changes_tag(&link.tag).map(|tag| (tag.finished_at, link)))
+        .filter(|(finished_at, _)| match finished_before {
+            Some(finished_before) => finished_at < &finished_before,
+            None => true,
+        })
+        .collect();
+
+    // From the latest to the earliest
+    candidates.sort_by(|(a_finished_at, a), (b_finished_at, b)| {
+        b_finished_at
+            .cmp(a_finished_at)
+            .then_with(|| b.target.get_raw_39().cmp(a.target.get_raw_39()))
+    });
+
+    for (finished_at, link) in candidates {
+        let element = match get(link.target, GetOptions::default())? {
+            Some(element) => element,
+            None => continue,
+        };
+
+        let rating_change: RatingChangeEntry = match element.entry().to_app_option()? {
+            Some(rating_change) => rating_change,
+            None => continue,
+        };
+
+        if !rating_change.new_ratings.contains_key(&agent) {
+            continue;
+        }
+
+        let last_move = get(
+            HeaderHash::from(rating_change.last_move_hash.clone()),
+            GetOptions::default(),
+        )?;
+
+        match last_move {
+            Some(last_move) if last_move.header().timestamp().eq(&finished_at) => {
+                return Ok(Some((
+                    element.header_address().clone().into(),
+                    rating_change,
+                )))
+            }
+            _ => continue,
+        }
+    }
+
+    Ok(None)
+}
+
+/**
+ * Whether the given agent already has a rating change for the given game, looking at the game stored in the tags of their links
+ */
+fn is_game_rated_for(agent: AgentPubKeyB64, game_hash: &EntryHashB64) -> ExternResult<bool> {
+    let links = get_links(AgentPubKey::from(agent).into(), Some(rating_changes_tag()))?;
+
+    Ok(links.iter().any(|link| {
+        parse_rating_changes_tag(&link.tag)
+            .and_then(|tag| tag.game_hash)
+            .map(|rated_game_hash| rated_game_hash.eq(game_hash))
+            .unwrap_or(false)
+    }))
+}
+
+fn leaderboard_path() -> Path {
+    Path::from(vec![Component::from(String::from("leaderboard"))])
+}
+
+fn rating_change_tag() -> LinkTag {
+    LinkTag::new("rating_change")
+}
+
+fn rating_changes_tag() -> LinkTag {
+    LinkTag::new("rating_changes")
+}
+
+/**
+ * Contents of the tag of the links from each player to their rating changes
+ */
+#[derive(Serialize, Deserialize, Debug, SerializedBytes)]
+struct RatingChangesTag {
+    // Timestamp of the last move of the rated game
+    finished_at: Timestamp,
+    // Rated game, so that each player is only rated once per game
+    #[serde(default)]
+    game_hash: Option<EntryHashB64>,
+}
+
+/**
+ * The "rating_changes" tag followed by the serialized contents, so that all of them are found by its prefix
+ */
+fn rating_changes_tag_with(tag: RatingChangesTag) -> ExternResult<LinkTag> {
+    let bytes = SerializedBytes::try_from(tag)?;
+
+    let mut tag_bytes = rating_changes_tag().0;
+    tag_bytes.extend(bytes.bytes());
+
+    Ok(LinkTag(tag_bytes))
+}
+
+fn parse_rating_changes_tag(tag: &LinkTag) -> Option<RatingChangesTag> {
+    let bytes = tag.0.strip_prefix(rating_changes_tag().0.as_slice())?;
+
+    RatingChangesTag::try_from(SerializedBytes::from(UnsafeBytes::from(bytes.to_vec()))).ok()
+}
+
+fn leaderboard_tag() -> LinkTag {
+    LinkTag::new("leaderboard")
+}
@@ -0,0 +1,9 @@
+mod elo;
+mod entry;
+mod handlers;
+mod validation;
+
+pub use elo::*;
+pub use entry::*;
+pub use handlers::*;
+pub use validation::*;
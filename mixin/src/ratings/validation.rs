@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use super::{elo_ratings, rating_of, RatingChangeEntry, INITIAL_RATING};
+use crate::game::GameEntry;
+use crate::outcome::final_outcome;
+use crate::turn_based_game::TurnBasedGame;
+
+/**
+ * Validates the rating change, returning error if:
+ *
+ * - The author of the rating change is not one of the players
+ * - The game has not finished at the referenced last move
+ * - A previous rating change doesn't include its player, is of this same game, or is of a game that didn't finish before this one
+ * - The previous rating change of a player is older than the previous rating change of a rival that also rated them,
+ *   which means that it is not their last rating change before this game
+ * - The new ratings are not the ones that result from the previous ratings and the outcome of the game
+ */
+pub fn validate_rating_change_entry<G: TurnBasedGame>(
+    author: AgentPubKey,
+    rating_change: RatingChangeEntry,
+) -> ExternResult<ValidateCallbackResult> {
+    let entry_hashed = must_get_entry(EntryHash::from(rating_change.game_hash.clone()))?;
+    let game: GameEntry = entry_hashed.as_content().try_into()?;
+
+    if game.seat_of(&AgentPubKeyB64::from(author)).is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The author of the rating change is not playing the game".into(),
+        ));
+    }
+
+    let rated_players: Vec<AgentPubKeyB64> = rating_change
+        .previous_rating_changes
+        .keys()
+        .cloned()
+        .collect();
+    let mut players = game.players.clone();
+    players.sort();
+
+    if !players.eq(&rated_players) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The rated players are not the players of the game".into(),
+        ));
+    }
+
+    let outcome = match final_outcome::<G>(
+        &game,
+        &rating_change.game_hash,
+        &rating_change.last_move_hash,
+    ) {
+        Ok(Some(outcome)) => outcome,
+        Ok(None) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The game had not finished at the given last move".into(),
+            ))
+        }
+        Err(error) => return Ok(ValidateCallbackResult::Invalid(format!("{:?}", error))),
+    };
+
+    let finished_at = must_get_header(rating_change.last_move_hash.clone().into())?
+        .header()
+        .timestamp();
+
+    let mut previous_ratings = BTreeMap::new();
+    let mut previous_rating_changes: BTreeMap<AgentPubKeyB64, (Timestamp, RatingChangeEntry)> =
+        BTreeMap::new();
+
+    for (player, maybe_previous_rating_change) in rating_change.previous_rating_changes.iter() {
+        let previous_rating = match maybe_previous_rating_change {
+            None => INITIAL_RATING,
+            Some(previous_rating_change_hash) => {
+                let element = must_get_valid_element(previous_rating_change_hash.clone().into())?;
+
+                let previous_rating_change: RatingChangeEntry =
+                    match element.entry().to_app_option()? {
+                        Some(previous_rating_change) => previous_rating_change,
+                        None => {
+                            return Ok(ValidateCallbackResult::Invalid(
+                                "The previous rating change is not a rating change".into(),
+                            ))
+                        }
+                    };
+
+                if previous_rating_change
+                    .game_hash
+                    .eq(&rating_change.game_hash)
+                {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "The previous rating change is of this same game".into(),
+                    ));
+                }
+
+                let previous_finished_at =
+                    must_get_header(previous_rating_change.last_move_hash.clone().into())?
+                        .header()
+                        .timestamp();
+
+                if previous_finished_at >= finished_at {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "The previous rating change is of a game that didn't finish before this one"
+                            .into(),
+                    ));
+                }
+
+                let rating = match rating_of(&previous_rating_change, player) {
+                    Ok(rating) => rating,
+                    Err(error) => {
+                        return Ok(ValidateCallbackResult::Invalid(format!("{:?}", error)))
+                    }
+                };
+
+                previous_rating_changes.insert(
+                    player.clone(),
+                    (previous_finished_at, previous_rating_change),
+                );
+
+                rating
+            }
+        };
+
+        previous_ratings.insert(player.clone(), previous_rating);
+    }
+
+    for (rival, (rival_finished_at, rival_rating_change)) in previous_rating_changes.iter() {
+        for player in rival_rating_change.new_ratings.keys() {
+            if player.eq(rival) || !previous_ratings.contains_key(player) {
+                continue;
+            }
+
+            let is_older = match previous_rating_changes.get(player) {
+                None => true,
+                Some((finished_at, _)) => finished_at < rival_finished_at,
+            };
+
+            if is_older {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "The previous rating change of player {} is not their last one before this game",
+                    player
+                )));
+            }
+        }
+    }
+
+    match elo_ratings(&previous_ratings, &outcome) {
+        Ok(new_ratings) if new_ratings.eq(&rating_change.new_ratings) => {
+            Ok(ValidateCallbackResult::Valid)
+        }
+        Ok(_) => Ok(ValidateCallbackResult::Invalid(
+            "The new ratings are not the ones that result from the game".into(),
+        )),
+        Err(error) => Ok(ValidateCallbackResult::Invalid(format!("{:?}", error))),
+    }
+}
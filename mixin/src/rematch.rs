@@ -0,0 +1,191 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    current_games::remove_current_game_for_agent,
+    game::{create_game_entry, get_game, get_game_status, GameEntry},
+    game_index::unindex_game,
+    signal::{send_signal_to_players, SignalPayload},
+    turn_based_game::{GameStatus, TurnBasedGame},
+};
+
+/** Public handlers */
+
+/**
+ * Offers the rest of players of the finished game to play it again
+ *
+ * If swap_seats is true, the rematch starts from the second seat, so that every seat moves in a different order
+ */
+pub fn offer_rematch<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    swap_seats: bool,
+) -> ExternResult<()> {
+    let game = get_game(game_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    verify_can_rematch::<G>(game_hash.clone(), &game, &my_pub_key)?;
+
+    create_link(
+        EntryHash::from(game_hash.clone()),
+        AgentPubKey::from(my_pub_key.clone()).into(),
+        LinkType(0),
+        rematch_offer_tag(swap_seats),
+    )?;
+
+    let signal = SignalPayload::RematchOffered {
+        game_hash,
+        offerer: my_pub_key,
+        swap_seats,
+    };
+
+    send_signal_to_players(game, signal)
+}
+
+/**
+ * Accepts the rematch offered by another player of the game, creating the new game and linking the old one to it
+ *
+ * The new game has the same teams, settings and options as the old one
+ *
+ * If the rematch has already started, because another player accepted it first, returns it instead of creating another one
+ */
+pub fn accept_rematch<G: TurnBasedGame>(game_hash: EntryHashB64) -> ExternResult<EntryHashB64> {
+    if let Some(rematch_hash) = get_rematch(game_hash.clone())? {
+        return Ok(rematch_hash);
+    }
+
+    let game = get_game(game_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    verify_can_rematch::<G>(game_hash.clone(), &game, &my_pub_key)?;
+
+    let offer_links = get_links(
+        EntryHash::from(game_hash.clone()),
+        Some(LinkTag::new("rematch_offer")),
+    )?;
+
+    let offer = offer_links
+        .iter()
+        .find(|link| {
+            let offerer: AgentPubKeyB64 = link
+                .target
+                .clone()
+                .retype(holo_hash::hash_type::Agent)
+                .into();
+            !offerer.eq(&my_pub_key) && game.seat_of(&offerer).is_some()
+        })
+        .ok_or(WasmError::Guest(
+            "No other player has offered a rematch for this game".into(),
+        ))?;
+
+    let mut teams = game.teams();
+    if offer.tag.eq(&rematch_offer_tag(true)) {
+        teams.rotate_left(1);
+    }
+
+    let rematch_hash = create_game_entry(
+        teams,
+        game.settings.clone(),
+        game.options.clone(),
+        None,
+        None,
+    )?;
+
+    let rematch_link_hash = create_link(
+        EntryHash::from(game_hash.clone()),
+        EntryHash::from(rematch_hash.clone()),
+        LinkType(0),
+        rematch_tag(),
+    )?;
+
+    // Players that accepted at the same time all converge to the first rematch that was linked
+    let first_rematch_hash = get_rematch(game_hash.clone())?.unwrap_or(rematch_hash.clone());
+
+    if !first_rematch_hash.eq(&rematch_hash) {
+        discard_rematch(rematch_hash, rematch_link_hash)?;
+    }
+
+    for link in offer_links {
+        delete_link(link.create_link_hash)?;
+    }
+
+    let signal = SignalPayload::RematchStarted {
+        game_hash,
+        rematch_hash: first_rematch_hash.clone(),
+    };
+
+    send_signal_to_players(game, signal)?;
+
+    Ok(first_rematch_hash)
+}
+
+/**
+ * Gets the rematch of the given game, if its players have played it again
+ *
+ * If several players accepted the rematch at the same time, the first one linked to the game is the rematch
+ *
+ * Following the rematches of each game returns the whole series of games
+ */
+pub fn get_rematch(game_hash: EntryHashB64) -> ExternResult<Option<EntryHashB64>> {
+    let links = get_links(EntryHash::from(game_hash), Some(rematch_tag()))?;
+
+    Ok(links
+        .into_iter()
+        .min_by_key(|link| (link.timestamp, link.target.get_raw_39().to_vec()))
+        .map(|link| link.target.into()))
+}
+
+/** Private helpers */
+
+/**
+ * Undoes the rematch created by a player that lost the race to accept it,
+ * so that it's not listed anywhere while its players play the first one
+ */
+fn discard_rematch(rematch_hash: EntryHashB64, rematch_link_hash: HeaderHash) -> ExternResult<()> {
+    let rematch = get_game(rematch_hash.clone())?;
+
+    delete_link(rematch_link_hash)?;
+
+    for member in rematch.members() {
+        remove_current_game_for_agent(rematch_hash.clone().into(), member.into())?;
+    }
+
+    unindex_game(rematch_hash, &rematch)
+}
+
+fn verify_can_rematch<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    game: &GameEntry,
+    agent: &AgentPubKeyB64,
+) -> ExternResult<()> {
+    if game.seat_of(agent).is_none() {
+        return Err(WasmError::Guest(
+            "Only the players of the game can play its rematch".into(),
+        ));
+    }
+
+    if let GameStatus::Ongoing = get_game_status::<G>(game_hash.clone())? {
+        return Err(WasmError::Guest(
+            "Cannot play a rematch until the game has finished".into(),
+        ));
+    }
+
+    if get_rematch(game_hash)?.is_some() {
+        return Err(WasmError::Guest(
+            "The rematch of this game has already started".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Offers with swapped seats share the prefix of the tag, so that both are found with a single query
+fn rematch_offer_tag(swap_seats: bool) -> LinkTag {
+    match swap_seats {
+        true => LinkTag::new("rematch_offer_swapped"),
+        false => LinkTag::new("rematch_offer"),
+    }
+}
+
+fn rematch_tag() -> LinkTag {
+    LinkTag::new("game->rematch")
+}
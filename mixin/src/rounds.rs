@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    commit_reveal::move_commitment,
+    game_move::{commit_move, GameMoveEntry, MoveType},
+    turn_based_game::TurnBasedGame,
+};
+
+/**
+ * State of the current round of a game played in simultaneous rounds, carried from move to move in their context
+ *
+ * In each round, all players seal their move and then reveal it; once all moves are revealed,
+ * they are resolved together with `apply_round`
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SimultaneousRound {
+    // Commitments to the moves of the current round
+    sealed: BTreeMap<AgentPubKeyB64, Vec<u8>>,
+    // Moves revealed in the current round
+    revealed: BTreeMap<AgentPubKeyB64, SerializedBytes>,
+}
+
+/** Public handlers */
+
+/**
+ * Seals our move for the current round, publishing only the hash of the move and a secret salt
+ *
+ * The move and the salt must be kept until all players have sealed their moves, and then revealed with `reveal_round_move`
+ */
+pub fn seal_round_move<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    game_move: G::GameMove,
+    salt: Vec<u8>,
+) -> ExternResult<HeaderHashB64> {
+    let move_bytes: SerializedBytes = game_move
+        .try_into()
+        .or(Err(WasmError::Guest("Couldn't serialize game move".into())))?;
+
+    let commitment = move_commitment(&move_bytes, &salt)?;
+
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::SealRoundMove { commitment },
+    )
+}
+
+/**
+ * Reveals the move that we sealed for the current round
+ *
+ * Only valid once all players have sealed their moves; the last player to reveal resolves the round
+ */
+pub fn reveal_round_move<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    game_move: G::GameMove,
+    salt: Vec<u8>,
+) -> ExternResult<HeaderHashB64> {
+    let move_bytes: SerializedBytes = game_move
+        .try_into()
+        .or(Err(WasmError::Guest("Couldn't serialize game move".into())))?;
+
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::RevealRoundMove {
+            game_move: move_bytes,
+            salt,
+        },
+    )
+}
+
+/** Helper functions */
+
+impl SimultaneousRound {
+    /**
+     * Updates the state of the current round with the given move
+     */
+    pub(crate) fn observe(&mut self, players: &Vec<AgentPubKeyB64>, game_move: &GameMoveEntry) {
+        match &game_move.game_move {
+            MoveType::SealRoundMove { commitment } => {
+                self.sealed
+                    .insert(game_move.author_pub_key.clone(), commitment.clone());
+            }
+            MoveType::RevealRoundMove {
+                game_move: move_bytes,
+                ..
+            } => {
+                self.revealed
+                    .insert(game_move.author_pub_key.clone(), move_bytes.clone());
+
+                if players.iter().all(|p| self.revealed.contains_key(p)) {
+                    self.sealed.clear();
+                    self.revealed.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /**
+     * Players that the current round is waiting for: the ones that haven't sealed their move,
+     * or the ones that haven't revealed it once all moves are sealed
+     */
+    pub(crate) fn pending_players(&self, players: &Vec<AgentPubKeyB64>) -> Vec<AgentPubKeyB64> {
+        let all_sealed = players.iter().all(|p| self.sealed.contains_key(p));
+
+        players
+            .iter()
+            .filter(|p| match all_sealed {
+                false => !self.sealed.contains_key(p),
+                true => !self.revealed.contains_key(p),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /**
+     * Returns all the moves of the round if the given reveal is the last one missing, or None otherwise
+     */
+    pub(crate) fn completed_by(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        author: &AgentPubKeyB64,
+        move_bytes: &SerializedBytes,
+    ) -> Option<BTreeMap<AgentPubKeyB64, SerializedBytes>> {
+        let mut round_moves = self.revealed.clone();
+        round_moves.insert(author.clone(), move_bytes.clone());
+
+        match players.iter().all(|p| round_moves.contains_key(p)) {
+            true => Some(round_moves),
+            false => None,
+        }
+    }
+
+    /**
+     * Returns error if the author can't seal a move in the current round
+     */
+    pub(crate) fn verify_can_seal<G: TurnBasedGame>(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        author: &AgentPubKeyB64,
+        commitment: &Vec<u8>,
+    ) -> ExternResult<()> {
+        verify_plays_in_rounds::<G>(true)?;
+
+        if commitment.len() != 32 {
+            return Err(WasmError::Guest(
+                "The commitment must be the SHA-256 hash of the move and the salt".into(),
+            ));
+        }
+        if !players.contains(author) {
+            return Err(WasmError::Guest(
+                "Only the players of the game can submit moves".into(),
+            ));
+        }
+        if self.sealed.contains_key(author) {
+            return Err(WasmError::Guest(
+                "Already submitted a move in the current round".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Returns error if the author can't reveal the given move in the current round
+     */
+    pub(crate) fn verify_can_reveal<G: TurnBasedGame>(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        author: &AgentPubKeyB64,
+        move_bytes: &SerializedBytes,
+        salt: &Vec<u8>,
+    ) -> ExternResult<()> {
+        verify_plays_in_rounds::<G>(true)?;
+
+        if !players.iter().all(|p| self.sealed.contains_key(p)) {
+            return Err(WasmError::Guest(
+                "Cannot reveal the move until all players have sealed theirs".into(),
+            ));
+        }
+        if self.revealed.contains_key(author) {
+            return Err(WasmError::Guest(
+                "Already revealed the move of the current round".into(),
+            ));
+        }
+
+        match self.sealed.get(author) {
+            Some(commitment) if move_commitment(move_bytes, salt)?.eq(commitment) => Ok(()),
+            _ => Err(WasmError::Guest(
+                "The revealed move doesn't match the sealed one".into(),
+            )),
+        }
+    }
+}
+
+/**
+ * Returns error if the game is not played in the given mode, rounds or turns
+ */
+pub(crate) fn verify_plays_in_rounds<G: TurnBasedGame>(in_rounds: bool) -> ExternResult<()> {
+    match (G::plays_in_rounds(), in_rounds) {
+        (true, false) => Err(WasmError::Guest(
+            "This game is played in simultaneous rounds, not in turns".into(),
+        )),
+        (false, true) => Err(WasmError::Guest(
+            "This game is played in turns, not in simultaneous rounds".into(),
+        )),
+        _ => Ok(()),
+    }
+}
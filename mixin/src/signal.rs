@@ -1,6 +1,9 @@
 use crate::game::GameEntry;
 use crate::game_move::MoveInfo;
-use hdk::prelude::holo_hash::EntryHashB64;
+use crate::invitation::{handle_invitation_accepted, Invitation};
+use crate::matchmaking::handle_game_request_join;
+use crate::spectators::get_game_spectators;
+use hdk::prelude::holo_hash::{AgentPubKeyB64, EntryHashB64};
 use hdk::prelude::*;
 
 #[derive(Serialize, Deserialize, Debug, SerializedBytes)]
@@ -14,16 +17,79 @@ pub enum SignalPayload {
     RemovedCurrentGame {
         game_hash: EntryHashB64,
     },
+    InvitationReceived {
+        invitation_hash: EntryHashB64,
+        invitation: Invitation,
+    },
+    InvitationAccepted {
+        invitation_hash: EntryHashB64,
+        invitee: AgentPubKeyB64,
+    },
+    InvitationRejected {
+        invitation_hash: EntryHashB64,
+        invitee: AgentPubKeyB64,
+    },
+    GameRequestJoinReceived {
+        game_request_hash: EntryHashB64,
+        joiner: AgentPubKeyB64,
+    },
+    GameRequestJoined {
+        game_request_hash: EntryHashB64,
+        game_hash: EntryHashB64,
+    },
+    RematchOffered {
+        game_hash: EntryHashB64,
+        offerer: AgentPubKeyB64,
+        swap_seats: bool,
+    },
+    MatchGameStarted {
+        match_hash: EntryHashB64,
+        game_hash: EntryHashB64,
+        // Number of the game in the match, starting from 1
+        game_number: u32,
+    },
+    TournamentGameReady {
+        tournament_hash: EntryHashB64,
+        round: u32,
+        game_hash: EntryHashB64,
+    },
+    RematchStarted {
+        game_hash: EntryHashB64,
+        rematch_hash: EntryHashB64,
+    },
 }
 
 /**
- * Send a remote signal to all players of the given game
+ * Send a remote signal to all players of the given game, including the teammates of each seat
  */
 pub fn send_signal_to_players(game: GameEntry, signal: SignalPayload) -> ExternResult<()> {
+    send_signal_to_agents(game.members(), signal)
+}
+
+/**
+ * Send a remote signal to all players and spectators of the given game
+ */
+pub fn send_signal_to_players_and_spectators(
+    game_hash: EntryHashB64,
+    game: GameEntry,
+    signal: SignalPayload,
+) -> ExternResult<()> {
+    let mut agents = game.members();
+    agents.append(&mut get_game_spectators(game_hash)?);
+
+    send_signal_to_agents(agents, signal)
+}
+
+/**
+ * Send a remote signal to all the given agents, except ourselves
+ */
+pub fn send_signal_to_agents(
+    agents: Vec<AgentPubKeyB64>,
+    signal: SignalPayload,
+) -> ExternResult<()> {
     let agent_info = agent_info()?;
 
-    let opponents: Vec<AgentPubKey> = game
-        .players
+    let opponents: Vec<AgentPubKey> = agents
         .into_iter()
         .map(|p| AgentPubKey::from(p))
         .filter(|player| player.clone() != agent_info.agent_latest_pubkey.clone())
@@ -36,10 +102,24 @@ pub fn send_signal_to_players(game: GameEntry, signal: SignalPayload) -> ExternR
 
 /**
  * Receives a new move made by an opponent and emits a signal
+ *
+ * When an invitee accepts an invitation that we sent, the game starts if they were the last one to accept it,
+ * and the same goes for the rivals that join a game request that we published
  */
 #[hdk_extern]
 fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
     let payload: SignalPayload = signal.decode()?;
     emit_signal(&payload)?;
+
+    match payload {
+        SignalPayload::InvitationAccepted {
+            invitation_hash, ..
+        } => handle_invitation_accepted(invitation_hash)?,
+        SignalPayload::GameRequestJoinReceived {
+            game_request_hash, ..
+        } => handle_game_request_join(game_request_hash)?,
+        _ => {}
+    }
+
     Ok(())
 }
@@ -0,0 +1,328 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::{
+    game::{
+        apply_move_type, build_game_state, game_status, is_finished, players_on_clock,
+        validate_players, GameEntry, GameSettings,
+    },
+    game_move::{verify_can_make_move, verify_time_left, GameMoveEntry, MoveType},
+    move_context::MoveContext,
+    outcome::game_outcome,
+    takeback::state_after_takeback,
+    time_control::{following_clock, initial_clock},
+    turn_based_game::{GameOutcome, GameStatus, TurnBasedGame},
+};
+
+/**
+ * Plays a game in memory, following the same rules as the zome functions and the validation of the mixin,
+ * but without a conductor, so that `TurnBasedGame` implementations can be tested in a plain `cargo test`
+ *
+ * All the moves of the mixin are supported, from meta-moves to simultaneous rounds, and time only passes
+ * when `advance_time` is called, so that clocks and inactivity can be tested too
+ */
+pub struct GameSimulator<G: TurnBasedGame> {
+    game: GameEntry,
+    moves: Vec<GameMoveEntry>,
+    // Moment in which each of the moves was made
+    made_at: Vec<DateTime<Utc>>,
+    // Context for the next move
+    context: MoveContext,
+    state: G,
+    now: DateTime<Utc>,
+}
+
+impl<G: TurnBasedGame + Clone> GameSimulator<G> {
+    /**
+     * Starts a game between the given players, with the default options of the game
+     */
+    pub fn new(players: Vec<AgentPubKeyB64>) -> ExternResult<Self> {
+        Self::new_with_options(players, G::GameOptions::default())
+    }
+
+    /**
+     * Starts a game between the given players, with the given options
+     *
+     * Returns error if the players can't play the game together, or if the options are not valid for the game
+     */
+    pub fn new_with_options(
+        players: Vec<AgentPubKeyB64>,
+        options: G::GameOptions,
+    ) -> ExternResult<Self> {
+        Self::new_with_settings(players, options, GameSettings::default())
+    }
+
+    /**
+     * Starts a game between the given players, with the given options and settings, like its time control
+     *
+     * Returns error if the players can't play the game together, or if the options are not valid for the game
+     */
+    pub fn new_with_settings(
+        players: Vec<AgentPubKeyB64>,
+        options: G::GameOptions,
+        settings: GameSettings,
+    ) -> ExternResult<Self> {
+        if let ValidateCallbackResult::Invalid(error) = validate_players::<G>(&players)? {
+            return Err(WasmError::Guest(error));
+        }
+
+        G::validate_options(&options, &players)?;
+
+        let options_bytes: SerializedBytes = options.try_into().or(Err(WasmError::Guest(
+            "Couldn't serialize game options".into(),
+        )))?;
+
+        let game = GameEntry {
+            players: players.clone(),
+            created_at: DateTime::from_utc(NaiveDateTime::from_timestamp(0, 0), Utc),
+            settings,
+            teammates: BTreeMap::new(),
+            options: Some(options_bytes),
+            match_hash: None,
+            previous_match_result: None,
+        };
+
+        let state = build_game_state::<G>(&game, &vec![])?;
+
+        Ok(GameSimulator {
+            now: game.created_at,
+            game,
+            moves: vec![],
+            made_at: vec![],
+            context: MoveContext::default(),
+            state,
+        })
+    }
+
+    /**
+     * Makes the given move defined by the game, returning the resulting state of the game
+     *
+     * Returns error if the game has finished, if it's not the turn of the author, or if the game rejects the move,
+     * in which case the state of the game doesn't change
+     */
+    pub fn play(&mut self, author: &AgentPubKeyB64, game_move: G::GameMove) -> ExternResult<G> {
+        let move_bytes: SerializedBytes = game_move
+            .try_into()
+            .or(Err(WasmError::Guest("Couldn't serialize game move".into())))?;
+
+        self.play_move(author, MoveType::Game(move_bytes))
+    }
+
+    /**
+     * Makes the given move of any type, returning the resulting state of the game
+     *
+     * The move is verified and applied as the validation of the mixin does, building the context of the next move from it
+     * Returns error if the move is not valid, in which case the state of the game doesn't change
+     */
+    pub fn play_move(&mut self, author: &AgentPubKeyB64, move_type: MoveType) -> ExternResult<G> {
+        let last_move = self.moves.last();
+
+        if is_finished(&self.state, last_move) {
+            return Err(WasmError::Guest(
+                "Game is already finished: cannot make any more moves".into(),
+            ));
+        }
+
+        let clock = match (&self.game.settings.time_control, last_move) {
+            (None, _) => None,
+            (Some(time_control), None) => Some(initial_clock(time_control, &self.game)),
+            (Some(time_control), Some(last_move)) => Some(following_clock(
+                time_control,
+                &self.game.players,
+                last_move,
+                self.made_at[self.made_at.len() - 1],
+            )?),
+        };
+
+        let mut move_entry = GameMoveEntry {
+            game_hash: EntryHash::from_raw_32(vec![0; 32]).into(),
+            author_pub_key: author.clone(),
+            signed_by: Some(author.clone()),
+            game_move: move_type,
+            // Replaced by the resulting state once the move is applied
+            resulting_game_state: serialize_state(self.state.clone())?,
+            previous_move_hash: self.moves.len().checked_sub(1).map(simulated_move_hash),
+            clock,
+            context: self.context.clone(),
+        };
+
+        verify_can_make_move(
+            &self.game,
+            &self.state,
+            &self.context,
+            last_move,
+            &move_entry,
+            self.now,
+            &|commit_move_hash| Ok(self.get_move(commit_move_hash.clone()).ok()),
+        )?;
+        verify_time_left(
+            &self.game,
+            players_on_clock(&self.state, &self.game, &self.context),
+            &move_entry,
+            self.now,
+        )?;
+
+        let new_state = match move_entry.game_move {
+            MoveType::AcceptTakeback => {
+                state_after_takeback::<G>(&self.game, &self.context, &|hash| self.get_move(hash))?
+            }
+            _ => apply_move_type(
+                self.state.clone(),
+                &move_entry.game_move,
+                author,
+                &self.game.players,
+                &self.context,
+            )?,
+        };
+        move_entry.resulting_game_state = serialize_state(new_state.clone())?;
+
+        let next_context = self.context.following(
+            &self.game.players,
+            &simulated_move_hash(self.moves.len()),
+            Timestamp::from_micros(self.now.timestamp_millis() * 1000),
+            &move_entry,
+            &|hash| self.get_move(hash),
+        )?;
+
+        self.moves.push(move_entry);
+        self.made_at.push(self.now);
+        self.context = next_context;
+        self.state = new_state.clone();
+
+        Ok(new_state)
+    }
+
+    /**
+     * Makes all the given moves in order, returning the state of the game after each of them
+     *
+     * Stops at the first move that fails, returning its error
+     */
+    pub fn play_all(&mut self, moves: Vec<(AgentPubKeyB64, G::GameMove)>) -> ExternResult<Vec<G>> {
+        moves
+            .into_iter()
+            .map(|(author, game_move)| self.play(&author, game_move))
+            .collect()
+    }
+
+    /**
+     * Makes all the given moves of any type in order, returning the state of the game after each of them
+     *
+     * Stops at the first move that fails, returning its error
+     */
+    pub fn play_moves(&mut self, moves: Vec<(AgentPubKeyB64, MoveType)>) -> ExternResult<Vec<G>> {
+        moves
+            .into_iter()
+            .map(|(author, move_type)| self.play_move(&author, move_type))
+            .collect()
+    }
+
+    /**
+     * Makes the given move, asserting that it fails, and returns its error
+     *
+     * Panics if the move succeeds
+     */
+    pub fn assert_invalid(&mut self, author: &AgentPubKeyB64, game_move: G::GameMove) -> WasmError {
+        match self.play(author, game_move) {
+            Ok(_) => panic!("Expected the move to be invalid, but it was accepted"),
+            Err(error) => error,
+        }
+    }
+
+    /**
+     * Makes the given move of any type, asserting that it fails, and returns its error
+     *
+     * Panics if the move succeeds
+     */
+    pub fn assert_invalid_move(
+        &mut self,
+        author: &AgentPubKeyB64,
+        move_type: MoveType,
+    ) -> WasmError {
+        match self.play_move(author, move_type) {
+            Ok(_) => panic!("Expected the move to be invalid, but it was accepted"),
+            Err(error) => error,
+        }
+    }
+
+    /**
+     * Moves the time of the game forward, which only passes when this is called
+     */
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.now = self.now + duration;
+    }
+
+    /**
+     * Current state of the game
+     */
+    pub fn state(&self) -> &G {
+        &self.state
+    }
+
+    /**
+     * Status of the game
+     */
+    pub fn status(&self) -> GameStatus {
+        game_status(&self.state, self.moves.last())
+    }
+
+    /**
+     * Outcome of the game, or None if it has not finished yet
+     */
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        game_outcome(&self.game, &self.state, self.moves.last())
+    }
+
+    /**
+     * Players that are allowed to make the next move
+     */
+    pub fn allowed_movers(&self) -> Vec<AgentPubKeyB64> {
+        self.state
+            .allowed_movers(&self.game.players, self.context.last_game_mover.as_ref())
+    }
+
+    /**
+     * Moves made so far
+     */
+    pub fn moves(&self) -> &Vec<GameMoveEntry> {
+        &self.moves
+    }
+
+    /**
+     * Gets the move with the given hash, as the simulated moves are identified by their position
+     */
+    fn get_move(&self, move_hash: HeaderHashB64) -> ExternResult<GameMoveEntry> {
+        (0..self.moves.len())
+            .find(|index| simulated_move_hash(*index).eq(&move_hash))
+            .map(|index| self.moves[index].clone())
+            .ok_or(WasmError::Guest(
+                "The move is not a move of this game".into(),
+            ))
+    }
+}
+
+/**
+ * Hash of the header of the simulated move in the given position
+ */
+pub fn simulated_move_hash(index: usize) -> HeaderHashB64 {
+    let mut bytes = (index as u32).to_le_bytes().to_vec();
+    bytes.resize(32, 0);
+
+    HeaderHash::from_raw_32(bytes).into()
+}
+
+fn serialize_state<G: TurnBasedGame>(state: G) -> ExternResult<SerializedBytes> {
+    state.try_into().or(Err(WasmError::Guest(
+        "Couldn't serialize game state".into(),
+    )))
+}
+
+/**
+ * Returns the given number of distinct agents, to use as the players of simulated games
+ */
+pub fn simulated_players(count: u8) -> Vec<AgentPubKeyB64> {
+    (0..count)
+        .map(|i| AgentPubKey::from_raw_32(vec![i; 32]).into())
+        .collect()
+}
@@ -0,0 +1,74 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::game::get_game;
+
+/** Public handlers */
+
+/**
+ * Starts watching the given game, so that we receive a signal for every new move
+ */
+pub fn watch_game(game_hash: EntryHashB64) -> ExternResult<()> {
+    let game = get_game(game_hash.clone())?;
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+
+    if game.seat_of(&my_pub_key.clone().into()).is_some() {
+        return Err(WasmError::Guest(
+            "Players already receive the moves of their games".into(),
+        ));
+    }
+
+    if get_spectators_links(game_hash.clone())?
+        .iter()
+        .any(|link| link.target.eq(&EntryHash::from(my_pub_key.clone())))
+    {
+        return Ok(());
+    }
+
+    create_link(
+        EntryHash::from(game_hash),
+        my_pub_key.into(),
+        LinkType(0),
+        spectators_tag(),
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Stops watching the given game
+ */
+pub fn unwatch_game(game_hash: EntryHashB64) -> ExternResult<()> {
+    let my_pub_key = EntryHash::from(agent_info()?.agent_latest_pubkey);
+
+    for link in get_spectators_links(game_hash)?
+        .into_iter()
+        .filter(|link| link.target.eq(&my_pub_key))
+    {
+        delete_link(link.create_link_hash)?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Gets all the agents that are watching the given game
+ */
+pub fn get_game_spectators(game_hash: EntryHashB64) -> ExternResult<Vec<AgentPubKeyB64>> {
+    let spectators: BTreeSet<AgentPubKeyB64> = get_spectators_links(game_hash)?
+        .into_iter()
+        .map(|link| link.target.retype(holo_hash::hash_type::Agent).into())
+        .collect();
+
+    Ok(spectators.into_iter().collect())
+}
+
+/** Private helpers */
+
+fn get_spectators_links(game_hash: EntryHashB64) -> ExternResult<Vec<Link>> {
+    get_links(EntryHash::from(game_hash), Some(spectators_tag()))
+}
+
+fn spectators_tag() -> LinkTag {
+    LinkTag::new("spectators")
+}
@@ -0,0 +1,157 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    game::{build_game_state, GameEntry},
+    game_move::{commit_move, GameMoveEntry, MoveType},
+    move_context::{first_reverted_game_move, MoveContext},
+    turn_based_game::TurnBasedGame,
+};
+
+/** Public handlers */
+
+/**
+ * Asks the rest of players to revert the last given number of game moves
+ *
+ * The request stays pending until the next move: if it's not an answer to the request, it's considered declined
+ */
+pub fn request_takeback<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+    moves: u32,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(
+        game_hash,
+        previous_move_hash,
+        MoveType::RequestTakeback { moves },
+    )
+}
+
+/**
+ * Reverts the game moves requested in the takeback request of the previous move
+ *
+ * The reverted moves are kept in the chain of moves, but they no longer count for the state of the game
+ */
+pub fn accept_takeback<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::AcceptTakeback)
+}
+
+/**
+ * Declines the takeback request made in the previous move, continuing the game
+ */
+pub fn decline_takeback<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    commit_move::<G>(game_hash, previous_move_hash, MoveType::DeclineTakeback)
+}
+
+/** Helper functions */
+
+/**
+ * Returns the indexes of the moves that have not been reverted by an accepted takeback
+ *
+ * The takeback request and its acceptance are superseded along with the moves they revert
+ */
+pub(crate) fn effective_move_indexes(moves: &Vec<GameMoveEntry>) -> Vec<usize> {
+    let mut effective: Vec<usize> = vec![];
+
+    for (index, game_move) in moves.iter().enumerate() {
+        match game_move.game_move {
+            MoveType::AcceptTakeback => {
+                let requested_moves = effective.last().and_then(|i| match moves[*i].game_move {
+                    MoveType::RequestTakeback { moves } => Some(moves as usize),
+                    _ => None,
+                });
+
+                let game_move_positions: Vec<usize> = effective
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, i)| moves[**i].game_move.is_game_move())
+                    .map(|(position, _)| position)
+                    .collect();
+
+                if let Some(first_reverted) = requested_moves
+                    .and_then(|n| game_move_positions.len().checked_sub(n))
+                    .and_then(|position| game_move_positions.get(position))
+                {
+                    effective.truncate(*first_reverted);
+                }
+            }
+            _ => effective.push(index),
+        }
+    }
+
+    effective
+}
+
+/**
+ * Returns the moves that have not been reverted by an accepted takeback
+ */
+pub(crate) fn effective_moves(moves: &Vec<GameMoveEntry>) -> Vec<GameMoveEntry> {
+    effective_move_indexes(moves)
+        .into_iter()
+        .map(|i| moves[i].clone())
+        .collect()
+}
+
+/**
+ * Builds the state of the game after accepting the takeback request made in the given context,
+ * which is the state before the first reverted game move, getting the reverted moves with the given function
+ */
+pub(crate) fn state_after_takeback<G: TurnBasedGame>(
+    game: &GameEntry,
+    context: &MoveContext,
+    get_move: &dyn Fn(HeaderHashB64) -> ExternResult<GameMoveEntry>,
+) -> ExternResult<G> {
+    let first_reverted = first_reverted_game_move(context, get_move)?;
+
+    match first_reverted.previous_move_hash {
+        None => build_game_state::<G>(game, &vec![]),
+        Some(previous_move_hash) => G::try_from(get_move(previous_move_hash)?.resulting_game_state)
+            .or(Err(WasmError::Guest(
+                "Couldn't deserialize game state".into(),
+            ))),
+    }
+}
+
+/**
+ * Returns error if the requested number of moves can't be taken back, given the context of the request
+ */
+pub(crate) fn verify_can_request_takeback(
+    context: &MoveContext,
+    requested_moves: u32,
+) -> ExternResult<()> {
+    let game_moves_count = context.game_moves_count;
+
+    match requested_moves > 0 && requested_moves <= game_moves_count {
+        true => Ok(()),
+        false => Err(WasmError::Guest(format!(
+            "Cannot take back {} moves: there are {} moves to take back",
+            requested_moves, game_moves_count
+        ))),
+    }
+}
+
+/**
+ * Returns error if the last move is not a takeback request made by another player
+ */
+pub(crate) fn verify_can_answer_takeback_request(
+    last_move: Option<&GameMoveEntry>,
+    author: &AgentPubKeyB64,
+) -> ExternResult<()> {
+    match last_move {
+        Some(last_move)
+            if matches!(last_move.game_move, MoveType::RequestTakeback { .. })
+                && !last_move.author_pub_key.eq(author) =>
+        {
+            Ok(())
+        }
+        _ => Err(WasmError::Guest(
+            "There is no takeback request from another player to answer".into(),
+        )),
+    }
+}
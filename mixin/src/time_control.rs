@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+use crate::{
+    current_games::remove_current_game,
+    game::{get_current_game_state, get_game, players_on_clock, GameEntry},
+    game_move::{commit_move, get_last_move, GameMoveEntry, MoveType},
+    move_context::next_move_context,
+    turn_based_game::TurnBasedGame,
+};
+
+/**
+ * Time settings for a game
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimeControl {
+    // Maximum time that a player can spend on a single move
+    pub move_time_limit_ms: Option<i64>,
+    // Total time that each player has for all their moves
+    pub time_bank_ms: Option<i64>,
+    // Time added to the time bank of a player after each of their moves
+    pub increment_ms: i64,
+}
+
+/**
+ * State of the clocks at the moment in which a turn started, stored in each move
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MoveClock {
+    // Timestamp of the previous move, or the creation of the game for the first move
+    #[serde(with = "ts_milliseconds")]
+    pub turn_started_at: DateTime<Utc>,
+    // Time left in the time bank of each player, empty if the game has no time bank
+    pub time_banks_ms: BTreeMap<AgentPubKeyB64, i64>,
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemainingTime {
+    // Players whose clock is running
+    pub players_on_clock: Vec<AgentPubKeyB64>,
+    // Time left in the time bank of each player, empty if the game has no time bank
+    pub time_banks_ms: BTreeMap<AgentPubKeyB64, i64>,
+    // Time left for the current move, if the game has a per-move limit
+    pub move_time_left_ms: Option<i64>,
+}
+
+/** Public handlers */
+
+/**
+ * Gets the time that each player has left at this moment
+ */
+pub fn get_remaining_time<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+) -> ExternResult<RemainingTime> {
+    let game = get_game(game_hash.clone())?;
+    let time_control = game_time_control(&game)?;
+
+    let last_move = get_last_move(game_hash.clone())?;
+    let game_state =
+        get_current_game_state::<G>(game_hash, &game, last_move.as_ref().map(|(_, m)| m))?;
+
+    let clock = next_move_clock(&game, &time_control, last_move.as_ref())?;
+
+    let context = next_move_context(&game.players, last_move.as_ref())?;
+    let players_on_clock = players_on_clock(&game_state, &game, &context);
+
+    Ok(remaining_time(
+        &time_control,
+        &clock,
+        players_on_clock,
+        now()?,
+    ))
+}
+
+/**
+ * Finishes the game by claiming the victory, given that the time of the player on turn has run out,
+ * and removes it from the current games
+ */
+pub fn claim_timeout_victory<G: TurnBasedGame>(
+    game_hash: EntryHashB64,
+    previous_move_hash: Option<HeaderHashB64>,
+) -> ExternResult<HeaderHashB64> {
+    let header_hash = commit_move::<G>(
+        game_hash.clone(),
+        previous_move_hash,
+        MoveType::ClaimTimeoutVictory,
+    )?;
+
+    remove_current_game(game_hash)?;
+
+    Ok(header_hash)
+}
+
+/** Helper functions */
+
+pub(crate) fn game_time_control(game: &GameEntry) -> ExternResult<TimeControl> {
+    game.settings
+        .time_control
+        .clone()
+        .ok_or(WasmError::Guest("This game has no time control".into()))
+}
+
+/**
+ * Builds the clock for the move that follows the given last move
+ */
+pub(crate) fn next_move_clock(
+    game: &GameEntry,
+    time_control: &TimeControl,
+    last_move: Option<&(HeaderHashB64, GameMoveEntry)>,
+) -> ExternResult<MoveClock> {
+    match last_move {
+        None => Ok(initial_clock(time_control, game)),
+        Some((last_move_hash, last_move)) => {
+            let element = get(
+                HeaderHash::from(last_move_hash.clone()),
+                GetOptions::default(),
+            )?
+            .ok_or(WasmError::Guest("Couldn't get the last move".into()))?;
+
+            following_clock(
+                time_control,
+                &game.players,
+                last_move,
+                timestamp_to_date_time(element.header().timestamp()),
+            )
+        }
+    }
+}
+
+/**
+ * Clock for the first move of the game
+ */
+pub(crate) fn initial_clock(time_control: &TimeControl, game: &GameEntry) -> MoveClock {
+    let time_banks_ms = match time_control.time_bank_ms {
+        Some(time_bank_ms) => game
+            .players
+            .iter()
+            .map(|player| (player.clone(), time_bank_ms))
+            .collect(),
+        None => BTreeMap::new(),
+    };
+
+    MoveClock {
+        turn_started_at: game.created_at,
+        time_banks_ms,
+    }
+}
+
+/**
+ * Clock for the move that follows the given move, which was made at the given moment
+ */
+pub(crate) fn following_clock(
+    time_control: &TimeControl,
+    players: &Vec<AgentPubKeyB64>,
+    previous_move: &GameMoveEntry,
+    previous_move_made_at: DateTime<Utc>,
+) -> ExternResult<MoveClock> {
+    let previous_clock = previous_move.clock.clone().ok_or(WasmError::Guest(
+        "The previous move has no clock in a game with time control".into(),
+    ))?;
+
+    // In games played in rounds, the clock only restarts when the round is completed,
+    // and each player is charged the time they took to reveal their move
+    if let MoveType::RevealRoundMove {
+        game_move: move_bytes,
+        ..
+    } = &previous_move.game_move
+    {
+        let author = &previous_move.author_pub_key;
+
+        if previous_move
+            .context
+            .round
+            .completed_by(players, author, move_bytes)
+            .is_none()
+        {
+            return Ok(previous_clock);
+        }
+
+        let mut time_banks_ms = previous_clock.time_banks_ms.clone();
+
+        for (player, time_bank_ms) in time_banks_ms.iter_mut() {
+            let revealed_at = match previous_move.context.last_move_at.get(player) {
+                Some(last_move_at) if !player.eq(author) => timestamp_to_date_time(*last_move_at),
+                _ => previous_move_made_at,
+            };
+
+            *time_bank_ms = *time_bank_ms - elapsed_ms(previous_clock.turn_started_at, revealed_at)
+                + time_control.increment_ms;
+        }
+
+        return Ok(MoveClock {
+            turn_started_at: previous_move_made_at,
+            time_banks_ms,
+        });
+    }
+
+    // Moves that are not defined by the game, like draw offers, don't consume time nor pass the turn
+    if !previous_move.game_move.is_game_move() {
+        return Ok(previous_clock);
+    }
+
+    let mut time_banks_ms = previous_clock.time_banks_ms.clone();
+
+    if let Some(time_bank_ms) = time_banks_ms.get_mut(&previous_move.author_pub_key) {
+        let elapsed_ms = elapsed_ms(previous_clock.turn_started_at, previous_move_made_at);
+        *time_bank_ms = *time_bank_ms - elapsed_ms + time_control.increment_ms;
+    }
+
+    Ok(MoveClock {
+        turn_started_at: previous_move_made_at,
+        time_banks_ms,
+    })
+}
+
+/**
+ * Computes the time left for every player at the given moment
+ */
+pub(crate) fn remaining_time(
+    time_control: &TimeControl,
+    clock: &MoveClock,
+    players_on_clock: Vec<AgentPubKeyB64>,
+    at: DateTime<Utc>,
+) -> RemainingTime {
+    let elapsed_ms = elapsed_ms(clock.turn_started_at, at);
+
+    let time_banks_ms = clock
+        .time_banks_ms
+        .iter()
+        .map(
+            |(player, time_bank_ms)| match players_on_clock.contains(player) {
+                true => (player.clone(), time_bank_ms - elapsed_ms),
+                false => (player.clone(), *time_bank_ms),
+            },
+        )
+        .collect();
+
+    RemainingTime {
+        players_on_clock,
+        time_banks_ms,
+        move_time_left_ms: time_control
+            .move_time_limit_ms
+            .map(|limit_ms| limit_ms - elapsed_ms),
+    }
+}
+
+/**
+ * Returns the players on clock whose time has run out at the given moment
+ */
+pub(crate) fn timed_out_players(
+    time_control: &TimeControl,
+    clock: &MoveClock,
+    players_on_clock: Vec<AgentPubKeyB64>,
+    at: DateTime<Utc>,
+) -> Vec<AgentPubKeyB64> {
+    let RemainingTime {
+        players_on_clock,
+        time_banks_ms,
+        move_time_left_ms,
+    } = remaining_time(time_control, clock, players_on_clock, at);
+
+    let move_time_over = match move_time_left_ms {
+        Some(move_time_left_ms) => move_time_left_ms < 0,
+        None => false,
+    };
+
+    players_on_clock
+        .into_iter()
+        .filter(|player| {
+            move_time_over
+                || match time_banks_ms.get(player) {
+                    Some(time_bank_ms) => *time_bank_ms < 0,
+                    None => false,
+                }
+        })
+        .collect()
+}
+
+/**
+ * Converts the timestamp to a date time truncated to milliseconds, as it's the precision with which they are stored
+ */
+pub(crate) fn timestamp_to_date_time(timestamp: Timestamp) -> DateTime<Utc> {
+    let (seconds, nanos) = timestamp.as_seconds_and_nanos();
+    let millis_in_nanos = nanos - nanos % 1_000_000;
+
+    DateTime::from_utc(NaiveDateTime::from_timestamp(seconds, millis_in_nanos), Utc)
+}
+
+pub(crate) fn now() -> ExternResult<DateTime<Utc>> {
+    Ok(timestamp_to_date_time(sys_time()?))
+}
+
+fn elapsed_ms(from: DateTime<Utc>, to: DateTime<Utc>) -> i64 {
+    to.signed_duration_since(from).num_milliseconds()
+}
@@ -0,0 +1,91 @@
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::convert::TryFrom;
+
+use crate::game::GameSettings;
+use crate::turn_based_game::GameStatus;
+
+/**
+ * Event in which the registered players play two-player games against each other in rounds
+ */
+#[hdk_entry(id = "tournament_entry")]
+#[derive(Clone)]
+pub struct TournamentEntry {
+    // Only the organizer can start the rounds of the tournament
+    pub organizer: AgentPubKeyB64,
+    pub name: String,
+    pub format: TournamentFormat,
+    pub settings: GameSettings,
+    // Serialized options of all the games of the tournament, None for the default ones
+    pub options: Option<SerializedBytes>,
+    #[serde(with = "ts_milliseconds")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TournamentFormat {
+    // Every player plays against every other player once
+    RoundRobin,
+    // Players with similar scores play against each other, for the given number of rounds
+    Swiss { rounds: u32 },
+    // Players are eliminated after losing one game
+    SingleElimination,
+    // Players are eliminated after losing two games
+    DoubleElimination,
+}
+
+/**
+ * Round of a tournament, with the games created for each of its pairings
+ */
+#[hdk_entry(id = "tournament_round")]
+#[derive(Clone)]
+pub struct TournamentRound {
+    pub tournament_hash: EntryHashB64,
+    // Number of the round, starting from 1
+    pub round: u32,
+    pub pairings: Vec<Pairing>,
+    // Registered players when the first round started, in the order of their seeds, which all rounds keep
+    #[serde(default)]
+    pub players: Vec<AgentPubKeyB64>,
+    // Previous round of the tournament, None for the first one
+    #[serde(default)]
+    pub previous_round: Option<EntryHashB64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Pairing {
+    // Players of the game, in the order in which they move, or only one player if they get a bye
+    pub players: Vec<AgentPubKeyB64>,
+    // None for byes, which count as a win for their player
+    pub game_hash: Option<EntryHashB64>,
+}
+
+// IO structs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TournamentStanding {
+    pub player: AgentPubKeyB64,
+    pub points: f64,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub byes: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TournamentState {
+    pub tournament: TournamentEntry,
+    // Registered players, in the order in which they registered, which is also their seed
+    pub players: Vec<AgentPubKeyB64>,
+    pub rounds: Vec<TournamentRound>,
+    // Players sorted by their points, and then by their seed
+    pub standings: Vec<TournamentStanding>,
+    pub status: GameStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TournamentInfo {
+    pub tournament_hash: EntryHashB64,
+    pub tournament: TournamentEntry,
+}
@@ -0,0 +1,364 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::{
+    game::{create_game_entry, GameSettings},
+    game_result::get_game_result_entry,
+    signal::{send_signal_to_agents, SignalPayload},
+    turn_based_game::{GameOutcome, GameStatus, TurnBasedGame},
+};
+
+use super::{
+    is_round_finished, next_round_pairings, tournament_standings, Pairing, TournamentEntry,
+    TournamentFormat, TournamentInfo, TournamentRound, TournamentState,
+};
+
+/** Public handlers */
+
+/**
+ * Creates a tournament organized by us, in which all its games are played with the given settings and options
+ *
+ * Players can register until the organizer starts its first round
+ */
+pub fn create_tournament<G: TurnBasedGame>(
+    name: String,
+    format: TournamentFormat,
+    settings: GameSettings,
+    options: G::GameOptions,
+) -> ExternResult<EntryHashB64> {
+    let now = sys_time()?.as_seconds_and_nanos();
+
+    let date_time = DateTime::from_utc(NaiveDateTime::from_timestamp(now.0, now.1), Utc);
+
+    let options_bytes: SerializedBytes = options.try_into().or(Err(WasmError::Guest(
+        "Couldn't serialize game options".into(),
+    )))?;
+
+    let tournament = TournamentEntry {
+        organizer: agent_info()?.agent_latest_pubkey.into(),
+        name,
+        format,
+        settings,
+        options: Some(options_bytes),
+        created_at: date_time,
+    };
+
+    create_entry(&tournament)?;
+
+    let tournament_hash = hash_entry(&tournament)?;
+
+    let path = tournaments_path();
+    path.ensure()?;
+
+    create_link(
+        path.hash()?,
+        tournament_hash.clone(),
+        LinkType(0),
+        tournaments_tag(),
+    )?;
+
+    Ok(tournament_hash.into())
+}
+
+/**
+ * Gets all the tournaments that have been created, from the most recent to the oldest one
+ */
+pub fn get_tournaments() -> ExternResult<Vec<TournamentInfo>> {
+    let mut links = get_links(tournaments_path().hash()?, Some(tournaments_tag()))?;
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    let get_inputs = links
+        .iter()
+        .map(|link| GetInput::new(link.target.clone().into(), GetOptions::default()))
+        .collect();
+
+    let elements = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+    let mut tournaments = vec![];
+
+    for (link, maybe_element) in links.into_iter().zip(elements.into_iter()) {
+        if let Some(element) = maybe_element {
+            let tournament: TournamentEntry = element
+                .entry()
+                .to_app_option()?
+                .ok_or(WasmError::Guest("Couldn't deserialize tournament".into()))?;
+
+            tournaments.push(TournamentInfo {
+                tournament_hash: link.target.into(),
+                tournament,
+            });
+        }
+    }
+
+    Ok(tournaments)
+}
+
+/**
+ * Registers us as a player of the tournament
+ *
+ * Players are seeded in the order in which they register
+ */
+pub fn register_for_tournament(tournament_hash: EntryHashB64) -> ExternResult<()> {
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if !get_tournament_rounds(tournament_hash.clone())?.is_empty() {
+        return Err(WasmError::Guest(
+            "Cannot register for a tournament that has already started".into(),
+        ));
+    }
+
+    if get_tournament_players(tournament_hash.clone())?.contains(&my_pub_key) {
+        return Ok(());
+    }
+
+    create_link(
+        EntryHash::from(tournament_hash),
+        AgentPubKey::from(my_pub_key).into(),
+        LinkType(0),
+        tournament_players_tag(),
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Starts the next round of the tournament, creating a game for each of its pairings and notifying their players
+ *
+ * Only the organizer can start a round, once all the games of the previous one have finished
+ */
+pub fn start_next_tournament_round(tournament_hash: EntryHashB64) -> ExternResult<EntryHashB64> {
+    let tournament = get_tournament(tournament_hash.clone())?;
+    let my_pub_key: AgentPubKeyB64 = agent_info()?.agent_latest_pubkey.into();
+
+    if !tournament.organizer.eq(&my_pub_key) {
+        return Err(WasmError::Guest(
+            "Only the organizer can start the rounds of the tournament".into(),
+        ));
+    }
+
+    let rounds = get_tournament_rounds(tournament_hash.clone())?;
+    let players = get_seeded_players(tournament_hash.clone(), &rounds)?;
+    let outcomes = get_tournament_outcomes(&rounds)?;
+
+    if !rounds
+        .iter()
+        .all(|round| is_round_finished(round, &outcomes))
+    {
+        return Err(WasmError::Guest(
+            "Cannot start the next round until all the games of the current one have finished"
+                .into(),
+        ));
+    }
+
+    let next_pairings = next_round_pairings(&tournament.format, &players, &rounds, &outcomes)
+        .ok_or(WasmError::Guest(
+            "The tournament has already finished, or it doesn't have enough players".into(),
+        ))?;
+
+    let round_number = rounds.len() as u32 + 1;
+    let previous_round = match rounds.last() {
+        None => None,
+        Some(last_round) => Some(hash_entry(last_round)?.into()),
+    };
+    let mut pairings = vec![];
+
+    for pairing_players in next_pairings {
+        let game_hash = match pairing_players.len() {
+            1 => None,
+            _ => Some(create_game_entry(
+                pairing_players.iter().map(|p| vec![p.clone()]).collect(),
+                tournament.settings.clone(),
+                tournament.options.clone(),
+                None,
+                None,
+            )?),
+        };
+
+        if let Some(game_hash) = game_hash.clone() {
+            let signal = SignalPayload::TournamentGameReady {
+                tournament_hash: tournament_hash.clone(),
+                round: round_number,
+                game_hash,
+            };
+
+            send_signal_to_agents(pairing_players.clone(), signal)?;
+        }
+
+        pairings.push(Pairing {
+            players: pairing_players,
+            game_hash,
+        });
+    }
+
+    let tournament_round = TournamentRound {
+        tournament_hash: tournament_hash.clone(),
+        round: round_number,
+        pairings,
+        players,
+        previous_round,
+    };
+
+    create_entry(&tournament_round)?;
+
+    let round_hash = hash_entry(&tournament_round)?;
+
+    create_link(
+        EntryHash::from(tournament_hash),
+        round_hash.clone(),
+        LinkType(0),
+        tournament_rounds_tag(),
+    )?;
+
+    Ok(round_hash.into())
+}
+
+/**
+ * Gets the tournament, with its players, its rounds, its standings and whether it has finished
+ */
+pub fn get_tournament_state(tournament_hash: EntryHashB64) -> ExternResult<TournamentState> {
+    let tournament = get_tournament(tournament_hash.clone())?;
+    let rounds = get_tournament_rounds(tournament_hash.clone())?;
+    let players = get_seeded_players(tournament_hash, &rounds)?;
+    let outcomes = get_tournament_outcomes(&rounds)?;
+
+    let all_rounds_finished = rounds
+        .iter()
+        .all(|round| is_round_finished(round, &outcomes));
+
+    let status = match !rounds.is_empty()
+        && all_rounds_finished
+        && next_round_pairings(&tournament.format, &players, &rounds, &outcomes).is_none()
+    {
+        true => GameStatus::Finished,
+        false => GameStatus::Ongoing,
+    };
+
+    let standings = tournament_standings(&players, &rounds, &outcomes);
+
+    Ok(TournamentState {
+        tournament,
+        players,
+        rounds,
+        standings,
+        status,
+    })
+}
+
+pub fn get_tournament(tournament_hash: EntryHashB64) -> ExternResult<TournamentEntry> {
+    let element = get(EntryHash::from(tournament_hash), GetOptions::default())?.ok_or(
+        WasmError::Guest("There is no tournament at this hash".into()),
+    )?;
+
+    element
+        .entry()
+        .to_app_option()?
+        .ok_or(WasmError::Guest("Couldn't deserialize tournament".into()))
+}
+
+/** Private helpers */
+
+/**
+ * Gets the registered players of the tournament, in the order in which they registered
+ */
+fn get_tournament_players(tournament_hash: EntryHashB64) -> ExternResult<Vec<AgentPubKeyB64>> {
+    let mut links = get_links(
+        EntryHash::from(tournament_hash),
+        Some(tournament_players_tag()),
+    )?;
+    links.sort_by_key(|link| link.timestamp);
+
+    let mut players: Vec<AgentPubKeyB64> = vec![];
+
+    for link in links {
+        let player: AgentPubKeyB64 = link.target.retype(holo_hash::hash_type::Agent).into();
+        if !players.contains(&player) {
+            players.push(player);
+        }
+    }
+
+    Ok(players)
+}
+
+/**
+ * Gets the players of the tournament in the order of their seeds, which are frozen in its first round
+ * so that registrations seen after it started don't change them
+ */
+fn get_seeded_players(
+    tournament_hash: EntryHashB64,
+    rounds: &Vec<TournamentRound>,
+) -> ExternResult<Vec<AgentPubKeyB64>> {
+    match rounds.first() {
+        Some(first_round) => Ok(first_round.players.clone()),
+        None => get_tournament_players(tournament_hash),
+    }
+}
+
+/**
+ * Gets the rounds of the tournament, sorted by their number
+ */
+fn get_tournament_rounds(tournament_hash: EntryHashB64) -> ExternResult<Vec<TournamentRound>> {
+    let links = get_links(
+        EntryHash::from(tournament_hash),
+        Some(tournament_rounds_tag()),
+    )?;
+
+    let get_inputs = links
+        .into_iter()
+        .map(|link| GetInput::new(link.target.into(), GetOptions::default()))
+        .collect();
+
+    let elements = HDK.with(|hdk| hdk.borrow().get(get_inputs))?;
+
+    let mut rounds = elements
+        .into_iter()
+        .filter_map(|maybe_element| maybe_element)
+        .map(|element| {
+            element.entry().to_app_option()?.ok_or(WasmError::Guest(
+                "Couldn't deserialize tournament round".into(),
+            ))
+        })
+        .collect::<ExternResult<Vec<TournamentRound>>>()?;
+
+    rounds.sort_by_key(|round| round.round);
+
+    Ok(rounds)
+}
+
+/**
+ * Gets the outcomes of the games of the given rounds that have finished
+ */
+fn get_tournament_outcomes(
+    rounds: &Vec<TournamentRound>,
+) -> ExternResult<BTreeMap<EntryHashB64, GameOutcome>> {
+    let mut outcomes = BTreeMap::new();
+
+    for game_hash in rounds
+        .iter()
+        .flat_map(|round| round.pairings.iter())
+        .filter_map(|pairing| pairing.game_hash.clone())
+    {
+        if let Some(game_result) = get_game_result_entry(game_hash.clone())? {
+            outcomes.insert(game_hash, game_result.outcome);
+        }
+    }
+
+    Ok(outcomes)
+}
+
+fn tournaments_path() -> Path {
+    Path::from("tournaments")
+}
+
+fn tournaments_tag() -> LinkTag {
+    LinkTag::new("tournaments")
+}
+
+fn tournament_players_tag() -> LinkTag {
+    LinkTag::new("tournament_players")
+}
+
+fn tournament_rounds_tag() -> LinkTag {
+    LinkTag::new("tournament_rounds")
+}
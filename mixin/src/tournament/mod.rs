@@ -0,0 +1,11 @@
+mod entry;
+mod handlers;
+mod pairings;
+#[cfg(test)]
+mod tests;
+mod validation;
+
+pub use entry::*;
+pub use handlers::*;
+pub use pairings::*;
+pub use validation::*;
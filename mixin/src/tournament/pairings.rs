@@ -0,0 +1,322 @@
+use hdk::prelude::holo_hash::*;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::turn_based_game::GameOutcome;
+
+use super::{TournamentFormat, TournamentRound, TournamentStanding};
+
+/** Helper functions */
+
+/**
+ * Computes the standings of the tournament from the outcomes of its finished games,
+ * sorted by points and then by the seed of the players
+ *
+ * Byes count as a win for their player
+ */
+pub(crate) fn tournament_standings(
+    players: &Vec<AgentPubKeyB64>,
+    rounds: &Vec<TournamentRound>,
+    outcomes: &BTreeMap<EntryHashB64, GameOutcome>,
+) -> Vec<TournamentStanding> {
+    let mut standings: BTreeMap<AgentPubKeyB64, TournamentStanding> = players
+        .iter()
+        .map(|player| {
+            (
+                player.clone(),
+                TournamentStanding {
+                    player: player.clone(),
+                    points: 0.0,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    byes: 0,
+                },
+            )
+        })
+        .collect();
+
+    for pairing in rounds.iter().flat_map(|round| round.pairings.iter()) {
+        for player in pairing.players.iter() {
+            let standing = match standings.get_mut(player) {
+                Some(standing) => standing,
+                None => continue,
+            };
+
+            match &pairing.game_hash {
+                None => {
+                    standing.byes += 1;
+                    standing.points += 1.0;
+                }
+                Some(game_hash) => {
+                    if let Some(outcome) = outcomes.get(game_hash) {
+                        standing.points += outcome.scores.get(player).cloned().unwrap_or(0.0);
+
+                        if outcome.winners.contains(player) {
+                            standing.wins += 1;
+                        } else if outcome.losers.contains(player) {
+                            standing.losses += 1;
+                        } else if outcome.draws.contains(player) {
+                            standing.draws += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Sorting is stable, so players with the same points keep the order of their seeds
+    let mut sorted_standings: Vec<TournamentStanding> = players
+        .iter()
+        .filter_map(|player| standings.remove(player))
+        .collect();
+    sorted_standings.sort_by(|a, b| b.points.partial_cmp(&a.points).unwrap_or(Ordering::Equal));
+
+    sorted_standings
+}
+
+/**
+ * Returns whether all the games of the round have finished
+ */
+pub(crate) fn is_round_finished(
+    round: &TournamentRound,
+    outcomes: &BTreeMap<EntryHashB64, GameOutcome>,
+) -> bool {
+    round
+        .pairings
+        .iter()
+        .all(|pairing| match &pairing.game_hash {
+            None => true,
+            Some(game_hash) => outcomes.contains_key(game_hash),
+        })
+}
+
+/**
+ * Computes the pairings of the next round of the tournament, given that all its previous rounds have finished
+ *
+ * Each pairing contains the players of a game, in the order in which they move, or only one player for a bye
+ * Returns None if the tournament has finished
+ */
+pub(crate) fn next_round_pairings(
+    format: &TournamentFormat,
+    players: &Vec<AgentPubKeyB64>,
+    rounds: &Vec<TournamentRound>,
+    outcomes: &BTreeMap<EntryHashB64, GameOutcome>,
+) -> Option<Vec<Vec<AgentPubKeyB64>>> {
+    if players.len() < 2 {
+        return None;
+    }
+
+    match format {
+        TournamentFormat::RoundRobin => round_robin_pairings(players, rounds.len()),
+        TournamentFormat::Swiss {
+            rounds: total_rounds,
+        } => match rounds.len() < *total_rounds as usize {
+            true => Some(swiss_pairings(players, rounds, outcomes)),
+            false => None,
+        },
+        TournamentFormat::SingleElimination => elimination_pairings(players, rounds, outcomes, 1),
+        TournamentFormat::DoubleElimination => elimination_pairings(players, rounds, outcomes, 2),
+    }
+}
+
+/** Private helpers */
+
+/**
+ * Pairs the players following the circle method, in which the first player stays fixed and the rest rotate
+ */
+fn round_robin_pairings(
+    players: &Vec<AgentPubKeyB64>,
+    round_index: usize,
+) -> Option<Vec<Vec<AgentPubKeyB64>>> {
+    let mut slots: Vec<Option<AgentPubKeyB64>> = players.iter().cloned().map(Some).collect();
+
+    // With an odd number of players, whoever is paired with the empty slot gets a bye
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+
+    let slots_count = slots.len();
+    if round_index >= slots_count - 1 {
+        return None;
+    }
+
+    let mut rest = slots.split_off(1);
+    rest.rotate_right(round_index);
+    slots.extend(rest);
+
+    let pairings = (0..slots_count / 2)
+        .filter_map(|i| {
+            let pairing: Vec<AgentPubKeyB64> =
+                vec![slots[i].clone(), slots[slots_count - 1 - i].clone()]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+
+            match pairing.len() {
+                0 => None,
+                // Alternate who moves first, so that each player starts in about half of their games
+                _ if (i + round_index) % 2 == 1 => Some(pairing.into_iter().rev().collect()),
+                _ => Some(pairing),
+            }
+        })
+        .collect();
+
+    Some(pairings)
+}
+
+/**
+ * Pairs each player, from the top of the standings down, with the next one they haven't played against yet
+ *
+ * With an odd number of players, the lowest ranked player that hasn't had a bye yet gets it
+ */
+fn swiss_pairings(
+    players: &Vec<AgentPubKeyB64>,
+    rounds: &Vec<TournamentRound>,
+    outcomes: &BTreeMap<EntryHashB64, GameOutcome>,
+) -> Vec<Vec<AgentPubKeyB64>> {
+    let standings = tournament_standings(players, rounds, outcomes);
+
+    let played: BTreeSet<(AgentPubKeyB64, AgentPubKeyB64)> = rounds
+        .iter()
+        .flat_map(|round| round.pairings.iter())
+        .filter(|pairing| pairing.players.len() == 2)
+        .flat_map(|pairing| {
+            vec![
+                (pairing.players[0].clone(), pairing.players[1].clone()),
+                (pairing.players[1].clone(), pairing.players[0].clone()),
+            ]
+        })
+        .collect();
+
+    let mut unpaired: Vec<AgentPubKeyB64> = standings.iter().map(|s| s.player.clone()).collect();
+    let mut pairings = vec![];
+
+    if unpaired.len() % 2 == 1 {
+        let bye_index = standings
+            .iter()
+            .rposition(|s| s.byes == 0)
+            .unwrap_or(unpaired.len() - 1);
+
+        pairings.push(vec![unpaired.remove(bye_index)]);
+    }
+
+    while !unpaired.is_empty() {
+        let player = unpaired.remove(0);
+
+        // If they have already played against everyone left, they play a rematch with the next one
+        let opponent_index = unpaired
+            .iter()
+            .position(|opponent| !played.contains(&(player.clone(), opponent.clone())))
+            .unwrap_or(0);
+        let opponent = unpaired.remove(opponent_index);
+
+        pairings.push(vec![player, opponent]);
+    }
+
+    pairings
+}
+
+/**
+ * Pairs the players that haven't been eliminated yet, each one against a player with the same number of losses
+ *
+ * Within each bracket, the top seed plays against the bottom seed, and with an odd number of players
+ * the top seed that hasn't had a bye yet gets it, so that byes don't always go to the same player
+ */
+fn elimination_pairings(
+    players: &Vec<AgentPubKeyB64>,
+    rounds: &Vec<TournamentRound>,
+    outcomes: &BTreeMap<EntryHashB64, GameOutcome>,
+    max_losses: u32,
+) -> Option<Vec<Vec<AgentPubKeyB64>>> {
+    let losses = elimination_losses(players, rounds, outcomes);
+    let losses_of = |player: &AgentPubKeyB64| losses.get(player).cloned().unwrap_or(0);
+    let had_bye = |player: &AgentPubKeyB64| {
+        rounds
+            .iter()
+            .flat_map(|round| round.pairings.iter())
+            .any(|pairing| pairing.game_hash.is_none() && pairing.players.contains(player))
+    };
+
+    let active: Vec<AgentPubKeyB64> = players
+        .iter()
+        .filter(|player| losses_of(player) < max_losses)
+        .cloned()
+        .collect();
+
+    if active.len() < 2 {
+        return None;
+    }
+
+    // The last two players play the final, even if they come from different brackets
+    if active.len() == 2 {
+        return Some(vec![active]);
+    }
+
+    let mut pairings = vec![];
+
+    for bracket_losses in 0..max_losses {
+        let mut bracket: Vec<AgentPubKeyB64> = active
+            .iter()
+            .filter(|player| losses_of(player) == bracket_losses)
+            .cloned()
+            .collect();
+
+        if bracket.len() % 2 == 1 {
+            let bye_index = bracket
+                .iter()
+                .position(|player| !had_bye(player))
+                .unwrap_or(0);
+
+            pairings.push(vec![bracket.remove(bye_index)]);
+        }
+
+        let bracket_size = bracket.len();
+        for i in 0..bracket_size / 2 {
+            pairings.push(vec![
+                bracket[i].clone(),
+                bracket[bracket_size - 1 - i].clone(),
+            ]);
+        }
+    }
+
+    Some(pairings)
+}
+
+/**
+ * Counts the games lost by each player
+ *
+ * Elimination games can't end in a draw: the player with the lowest seed is the one that loses them
+ */
+fn elimination_losses(
+    players: &Vec<AgentPubKeyB64>,
+    rounds: &Vec<TournamentRound>,
+    outcomes: &BTreeMap<EntryHashB64, GameOutcome>,
+) -> BTreeMap<AgentPubKeyB64, u32> {
+    let seed_of = |player: &AgentPubKeyB64| players.iter().position(|p| p.eq(player));
+    let mut losses: BTreeMap<AgentPubKeyB64, u32> = BTreeMap::new();
+
+    for pairing in rounds.iter().flat_map(|round| round.pairings.iter()) {
+        let outcome = match pairing.game_hash.as_ref().and_then(|h| outcomes.get(h)) {
+            Some(outcome) => outcome,
+            None => continue,
+        };
+
+        let losers: Vec<AgentPubKeyB64> = match outcome.winners.is_empty() {
+            true => pairing
+                .players
+                .iter()
+                .max_by_key(|player| seed_of(player))
+                .cloned()
+                .into_iter()
+                .collect(),
+            false => outcome.losers.clone(),
+        };
+
+        for loser in losers {
+            *losses.entry(loser).or_insert(0) += 1;
+        }
+    }
+
+    losses
+}
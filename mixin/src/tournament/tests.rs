@@ -0,0 +1,364 @@
+use hdk::prelude::holo_hash::*;
+use proptest::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::turn_based_game::{GameOutcome, OutcomeReason};
+
+use super::{
+    next_round_pairings, tournament_standings, Pairing, TournamentFormat, TournamentRound,
+};
+
+fn player(seed: usize) -> AgentPubKeyB64 {
+    AgentPubKey::from_raw_32(vec![seed as u8; 32]).into()
+}
+
+fn players(count: usize) -> Vec<AgentPubKeyB64> {
+    (0..count).map(player).collect()
+}
+
+fn game_hash(round: usize, pairing: usize) -> EntryHashB64 {
+    let mut bytes = vec![round as u8, pairing as u8];
+    bytes.resize(32, 0);
+    EntryHash::from_raw_32(bytes).into()
+}
+
+fn seed_of(players: &Vec<AgentPubKeyB64>, player: &AgentPubKeyB64) -> usize {
+    players.iter().position(|p| p.eq(player)).unwrap()
+}
+
+/**
+ * Outcome in which the player with the best seed of the game wins it
+ */
+fn top_seed_wins(players: &Vec<AgentPubKeyB64>, game_players: &Vec<AgentPubKeyB64>) -> GameOutcome {
+    let winner = game_players
+        .iter()
+        .min_by_key(|player| seed_of(players, player))
+        .unwrap()
+        .clone();
+
+    GameOutcome::victory(
+        vec![winner],
+        game_players,
+        OutcomeReason::GameRules("checkmate".into()),
+    )
+}
+
+/**
+ * Tournament played until there are no more rounds, deciding the outcome of each game with the given function
+ */
+struct PlayedTournament {
+    rounds: Vec<TournamentRound>,
+    outcomes: BTreeMap<EntryHashB64, GameOutcome>,
+}
+
+impl PlayedTournament {
+    fn play(
+        format: TournamentFormat,
+        players: &Vec<AgentPubKeyB64>,
+        outcome_of: &dyn Fn(&Vec<AgentPubKeyB64>) -> GameOutcome,
+    ) -> Self {
+        let mut tournament = PlayedTournament {
+            rounds: vec![],
+            outcomes: BTreeMap::new(),
+        };
+
+        while let Some(round_pairings) =
+            next_round_pairings(&format, players, &tournament.rounds, &tournament.outcomes)
+        {
+            let round_index = tournament.rounds.len();
+            assert!(round_index < 100, "The tournament never finishes");
+
+            let pairings = round_pairings
+                .into_iter()
+                .enumerate()
+                .map(|(i, pairing_players)| {
+                    let game_hash = match pairing_players.len() {
+                        1 => None,
+                        _ => {
+                            let game_hash = game_hash(round_index, i);
+                            tournament
+                                .outcomes
+                                .insert(game_hash.clone(), outcome_of(&pairing_players));
+                            Some(game_hash)
+                        }
+                    };
+
+                    Pairing {
+                        players: pairing_players,
+                        game_hash,
+                    }
+                })
+                .collect();
+
+            tournament.rounds.push(TournamentRound {
+                tournament_hash: EntryHash::from_raw_32(vec![0; 32]).into(),
+                round: round_index as u32 + 1,
+                pairings,
+                players: players.clone(),
+                previous_round: None,
+            });
+        }
+
+        tournament
+    }
+
+    fn round_pairings(&self) -> Vec<Vec<Vec<AgentPubKeyB64>>> {
+        self.rounds
+            .iter()
+            .map(|round| round.pairings.iter().map(|p| p.players.clone()).collect())
+            .collect()
+    }
+
+    fn byes(&self) -> Vec<AgentPubKeyB64> {
+        self.rounds
+            .iter()
+            .flat_map(|round| round.pairings.iter())
+            .filter(|pairing| pairing.game_hash.is_none())
+            .flat_map(|pairing| pairing.players.clone())
+            .collect()
+    }
+
+    fn games(&self) -> Vec<BTreeSet<AgentPubKeyB64>> {
+        self.rounds
+            .iter()
+            .flat_map(|round| round.pairings.iter())
+            .filter(|pairing| pairing.game_hash.is_some())
+            .map(|pairing| pairing.players.iter().cloned().collect())
+            .collect()
+    }
+}
+
+/**
+ * Every player appears exactly once in each round, either in a game or with a bye
+ */
+fn assert_each_player_once_per_round(tournament: &PlayedTournament, players: &Vec<AgentPubKeyB64>) {
+    for round in tournament.rounds.iter() {
+        let mut round_players: Vec<AgentPubKeyB64> = round
+            .pairings
+            .iter()
+            .flat_map(|pairing| pairing.players.clone())
+            .collect();
+        round_players.sort();
+
+        let mut expected = players.clone();
+        expected.sort();
+
+        assert_eq!(round_players, expected);
+    }
+}
+
+/**
+ * No player appears twice in the same round
+ */
+fn assert_no_player_twice_per_round(tournament: &PlayedTournament) {
+    for round in tournament.rounds.iter() {
+        let round_players: Vec<AgentPubKeyB64> = round
+            .pairings
+            .iter()
+            .flat_map(|pairing| pairing.players.clone())
+            .collect();
+        let distinct_players: BTreeSet<AgentPubKeyB64> = round_players.iter().cloned().collect();
+
+        assert_eq!(distinct_players.len(), round_players.len());
+    }
+}
+
+proptest! {
+    #[test]
+    fn round_robin_pairs_every_two_players_once(count in 2..12usize) {
+        let players = players(count);
+        let tournament = PlayedTournament::play(
+            TournamentFormat::RoundRobin,
+            &players,
+            &|game_players| top_seed_wins(&players, game_players),
+        );
+
+        let expected_rounds = match count % 2 {
+            0 => count - 1,
+            _ => count,
+        };
+        prop_assert_eq!(tournament.rounds.len(), expected_rounds);
+        assert_each_player_once_per_round(&tournament, &players);
+
+        let games = tournament.games();
+        let distinct_games: BTreeSet<BTreeSet<AgentPubKeyB64>> = games.iter().cloned().collect();
+        prop_assert_eq!(games.len(), count * (count - 1) / 2);
+        prop_assert_eq!(distinct_games.len(), games.len());
+    }
+
+    #[test]
+    fn round_robin_gives_one_bye_to_each_player_with_odd_player_counts(half in 1..6usize) {
+        let players = players(2 * half + 1);
+        let tournament = PlayedTournament::play(
+            TournamentFormat::RoundRobin,
+            &players,
+            &|game_players| top_seed_wins(&players, game_players),
+        );
+
+        let mut byes = tournament.byes();
+        byes.sort();
+
+        prop_assert_eq!(byes, players);
+    }
+
+    #[test]
+    fn swiss_gives_byes_to_different_players(half in 1..6usize, rounds in 1..6u32) {
+        let players = players(2 * half + 1);
+        let rounds = rounds.min(players.len() as u32);
+        let tournament = PlayedTournament::play(
+            TournamentFormat::Swiss { rounds },
+            &players,
+            &|game_players| top_seed_wins(&players, game_players),
+        );
+
+        prop_assert_eq!(tournament.rounds.len(), rounds as usize);
+        assert_each_player_once_per_round(&tournament, &players);
+
+        let byes = tournament.byes();
+        let distinct_byes: BTreeSet<AgentPubKeyB64> = byes.iter().cloned().collect();
+        prop_assert_eq!(byes.len(), rounds as usize);
+        prop_assert_eq!(distinct_byes.len(), byes.len());
+    }
+}
+
+#[test]
+fn swiss_pairs_players_with_the_same_points_and_avoids_rematches() {
+    let players = players(4);
+    let tournament = PlayedTournament::play(
+        TournamentFormat::Swiss { rounds: 3 },
+        &players,
+        &|game_players| top_seed_wins(&players, game_players),
+    );
+
+    let (p0, p1, p2, p3) = (player(0), player(1), player(2), player(3));
+
+    assert_eq!(
+        tournament.round_pairings(),
+        vec![
+            vec![vec![p0.clone(), p1.clone()], vec![p2.clone(), p3.clone()]],
+            // The winners and the losers of the first round play against each other
+            vec![vec![p0.clone(), p2.clone()], vec![p1.clone(), p3.clone()]],
+            vec![vec![p0, p3], vec![p1, p2]],
+        ]
+    );
+
+    let standings = tournament_standings(&players, &tournament.rounds, &tournament.outcomes);
+    let points: Vec<f64> = standings.iter().map(|s| s.points).collect();
+    assert_eq!(points, vec![3.0, 2.0, 1.0, 0.0]);
+}
+
+#[test]
+fn byes_count_as_a_win_in_the_standings() {
+    let players = players(3);
+    let tournament = PlayedTournament::play(
+        TournamentFormat::Swiss { rounds: 1 },
+        &players,
+        &|game_players| top_seed_wins(&players, game_players),
+    );
+
+    let standings = tournament_standings(&players, &tournament.rounds, &tournament.outcomes);
+    let bye_standing = standings
+        .iter()
+        .find(|s| s.player.eq(&tournament.byes()[0]))
+        .unwrap();
+
+    assert_eq!(bye_standing.byes, 1);
+    assert_eq!(bye_standing.points, 1.0);
+}
+
+#[test]
+fn single_elimination_rotates_the_byes_until_the_final() {
+    let players = players(5);
+    let tournament = PlayedTournament::play(
+        TournamentFormat::SingleElimination,
+        &players,
+        &|game_players| top_seed_wins(&players, game_players),
+    );
+
+    let (p0, p1, p2, p3, p4) = (player(0), player(1), player(2), player(3), player(4));
+
+    assert_eq!(
+        tournament.round_pairings(),
+        vec![
+            vec![vec![p0.clone()], vec![p1.clone(), p4], vec![p2.clone(), p3]],
+            // The top seed already had a bye, so the next one gets it
+            vec![vec![p1.clone()], vec![p0.clone(), p2]],
+            vec![vec![p0, p1]],
+        ]
+    );
+}
+
+#[test]
+fn single_elimination_draws_eliminate_the_lowest_seed() {
+    let players = players(2);
+    let rounds = vec![TournamentRound {
+        tournament_hash: EntryHash::from_raw_32(vec![0; 32]).into(),
+        round: 1,
+        pairings: vec![Pairing {
+            players: players.clone(),
+            game_hash: Some(game_hash(0, 0)),
+        }],
+        players: players.clone(),
+        previous_round: None,
+    }];
+    let mut outcomes = BTreeMap::new();
+    outcomes.insert(
+        game_hash(0, 0),
+        GameOutcome::draw(&players, OutcomeReason::Agreement),
+    );
+
+    assert_eq!(
+        next_round_pairings(
+            &TournamentFormat::SingleElimination,
+            &players,
+            &rounds,
+            &outcomes
+        ),
+        None
+    );
+}
+
+#[test]
+fn double_elimination_plays_the_winners_and_losers_brackets_until_the_final() {
+    let players = players(4);
+    let tournament = PlayedTournament::play(
+        TournamentFormat::DoubleElimination,
+        &players,
+        &|game_players| top_seed_wins(&players, game_players),
+    );
+
+    let (p0, p1, p2, p3) = (player(0), player(1), player(2), player(3));
+
+    assert_eq!(
+        tournament.round_pairings(),
+        vec![
+            vec![vec![p0.clone(), p3.clone()], vec![p1.clone(), p2.clone()]],
+            // Winners bracket, and then losers bracket
+            vec![vec![p0.clone(), p1.clone()], vec![p2.clone(), p3]],
+            // The only player without losses waits for the winner of the losers bracket
+            vec![vec![p0.clone()], vec![p1.clone(), p2]],
+            vec![vec![p0, p1]],
+        ]
+    );
+}
+
+#[test]
+fn elimination_finishes_when_only_one_player_is_left() {
+    for format in vec![
+        TournamentFormat::SingleElimination,
+        TournamentFormat::DoubleElimination,
+    ] {
+        for count in 2..10 {
+            let players = players(count);
+            let tournament = PlayedTournament::play(format.clone(), &players, &|game_players| {
+                top_seed_wins(&players, game_players)
+            });
+
+            assert_no_player_twice_per_round(&tournament);
+
+            let last_round = tournament.rounds.last().unwrap();
+            assert_eq!(last_round.pairings.len(), 1);
+            assert_eq!(last_round.pairings[0].players.len(), 2);
+        }
+    }
+}
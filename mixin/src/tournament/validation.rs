@@ -0,0 +1,176 @@
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use super::{TournamentEntry, TournamentFormat, TournamentRound};
+use crate::game::GameEntry;
+use crate::turn_based_game::TurnBasedGame;
+
+/**
+ * Validates the tournament, returning error if:
+ *
+ * - It's not created by its organizer
+ * - The game can't be played by two players
+ * - It's a Swiss tournament without rounds
+ * - Its options can't be deserialized into the options of the game
+ */
+pub fn validate_tournament_entry<G: TurnBasedGame>(
+    author: AgentPubKey,
+    tournament: TournamentEntry,
+) -> ExternResult<ValidateCallbackResult> {
+    if !tournament.organizer.eq(&AgentPubKeyB64::from(author)) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the organizer of a tournament can create it".into(),
+        ));
+    }
+
+    let two_players_allowed =
+        G::min_players().unwrap_or(0) <= 2 && G::max_players().unwrap_or(2) >= 2;
+    if !two_players_allowed {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Tournaments can only be played with two-player games".into(),
+        ));
+    }
+
+    if let TournamentFormat::Swiss { rounds: 0 } = tournament.format {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A Swiss tournament must have at least one round".into(),
+        ));
+    }
+
+    if let Some(options_bytes) = tournament.options {
+        if G::GameOptions::try_from(options_bytes).is_err() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Couldn't deserialize the options of the tournament".into(),
+            ));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/**
+ * Validates the round of the tournament, returning error if:
+ *
+ * - It's not created by the organizer of the tournament
+ * - Its number is 0, or it doesn't follow the previous round of the tournament
+ * - Its players are not the ones of the previous round, or there are less than two of them in the first round
+ * - Any pairing doesn't have a game for two players, or a bye for one player
+ * - Any paired player is not a player of the tournament, or is paired more than once
+ * - Any game is not played by the players of its pairing with the settings and options of the tournament
+ */
+pub fn validate_tournament_round(
+    author: AgentPubKey,
+    tournament_round: TournamentRound,
+) -> ExternResult<ValidateCallbackResult> {
+    let tournament: TournamentEntry =
+        must_get_entry(tournament_round.tournament_hash.clone().into())?
+            .as_content()
+            .try_into()?;
+
+    if !tournament.organizer.eq(&AgentPubKeyB64::from(author)) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the organizer of the tournament can start its rounds".into(),
+        ));
+    }
+
+    if tournament_round.round == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The rounds of a tournament start from 1".into(),
+        ));
+    }
+
+    match tournament_round.previous_round.clone() {
+        None => {
+            if tournament_round.round != 1 {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the first round of a tournament has no previous round".into(),
+                ));
+            }
+
+            let players: BTreeSet<&AgentPubKeyB64> = tournament_round.players.iter().collect();
+            if tournament_round.players.len() < 2 || players.len() != tournament_round.players.len()
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The first round of a tournament must have at least two different players"
+                        .into(),
+                ));
+            }
+        }
+        Some(previous_round_hash) => {
+            let previous_round: TournamentRound = must_get_entry(previous_round_hash.into())?
+                .as_content()
+                .try_into()?;
+
+            if !previous_round
+                .tournament_hash
+                .eq(&tournament_round.tournament_hash)
+                || previous_round.round + 1 != tournament_round.round
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The round doesn't follow the previous round of the tournament".into(),
+                ));
+            }
+
+            if !previous_round.players.eq(&tournament_round.players) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The players of the tournament can't change once it has started".into(),
+                ));
+            }
+        }
+    }
+
+    let valid_pairings = tournament_round.pairings.iter().all(|pairing| {
+        match (pairing.players.len(), &pairing.game_hash) {
+            (1, None) => true,
+            (2, Some(_)) => !pairing.players[0].eq(&pairing.players[1]),
+            _ => false,
+        }
+    });
+
+    if !valid_pairings {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Each pairing must be either a game between two players or a bye".into(),
+        ));
+    }
+
+    let mut paired_players = BTreeSet::new();
+    for player in tournament_round
+        .pairings
+        .iter()
+        .flat_map(|pairing| pairing.players.iter())
+    {
+        if !tournament_round.players.contains(player) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "The paired player {} is not a player of the tournament",
+                player
+            )));
+        }
+        if !paired_players.insert(player) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "The player {} is paired more than once in the round",
+                player
+            )));
+        }
+    }
+
+    for pairing in tournament_round.pairings.iter() {
+        if let Some(game_hash) = pairing.game_hash.clone() {
+            let game: GameEntry = must_get_entry(game_hash.into())?.as_content().try_into()?;
+
+            if !game.players.eq(&pairing.players)
+                || !game.teammates.is_empty()
+                || !game.settings.eq(&tournament.settings)
+                || !game.options.eq(&tournament.options)
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The game is not played by the players of its pairing with the settings and options of the tournament"
+                        .into(),
+                ));
+            }
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -1,17 +1,96 @@
+use std::collections::BTreeMap;
+
 use hdk::prelude::holo_hash::AgentPubKeyB64;
 use hdk::prelude::*;
 
+use crate::random_beacon::GameRng;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum GameStatus {
     Finished,
     Ongoing,
 }
 
+/**
+ * Result of a finished game
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GameOutcome {
+    pub winners: Vec<AgentPubKeyB64>,
+    pub losers: Vec<AgentPubKeyB64>,
+    // Players that finished the game in a draw
+    pub draws: Vec<AgentPubKeyB64>,
+    pub scores: BTreeMap<AgentPubKeyB64, f64>,
+    pub reason: OutcomeReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum OutcomeReason {
+    // The game finished following its own rules, e.g. "checkmate" or "three in a row"
+    GameRules(String),
+    Resignation,
+    Timeout,
+    Abandonment,
+    Agreement,
+}
+
+impl GameOutcome {
+    /**
+     * Outcome in which the winners get a score of 1 and the rest of players a score of 0
+     */
+    pub fn victory(
+        winners: Vec<AgentPubKeyB64>,
+        players: &Vec<AgentPubKeyB64>,
+        reason: OutcomeReason,
+    ) -> Self {
+        let losers: Vec<AgentPubKeyB64> = players
+            .iter()
+            .filter(|p| !winners.contains(p))
+            .cloned()
+            .collect();
+
+        let scores = players
+            .iter()
+            .map(|p| (p.clone(), if winners.contains(p) { 1.0 } else { 0.0 }))
+            .collect();
+
+        GameOutcome {
+            winners,
+            losers,
+            draws: vec![],
+            scores,
+            reason,
+        }
+    }
+
+    /**
+     * Outcome in which all players draw, each of them getting a score of 0.5
+     */
+    pub fn draw(players: &Vec<AgentPubKeyB64>, reason: OutcomeReason) -> Self {
+        GameOutcome {
+            winners: vec![],
+            losers: vec![],
+            draws: players.clone(),
+            scores: players.iter().map(|p| (p.clone(), 0.5)).collect(),
+            reason,
+        }
+    }
+}
+
+/**
+ * Options for games that can't be configured, to use as their `GameOptions`
+ */
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Default)]
+pub struct NoOptions;
+
 /**
  * Game trait that your game struct has to implement
  */
 pub trait TurnBasedGame: TryFrom<SerializedBytes> + TryInto<SerializedBytes> {
     type GameMove: TryFrom<SerializedBytes> + TryInto<SerializedBytes> + Clone;
+    // Rules that are chosen when creating the game, like the board size or a variant
+    // Use NoOptions if the game can't be configured
+    type GameOptions: TryFrom<SerializedBytes> + TryInto<SerializedBytes> + Clone + Default;
 
     // The minimum number of players that must participate for the game to be valid
     // Return None if there is no limit
@@ -21,13 +100,83 @@ pub trait TurnBasedGame: TryFrom<SerializedBytes> + TryInto<SerializedBytes> {
     // Return None if there is no limit
     fn max_players() -> Option<usize>;
 
-    // Constructs the initial state for the game
-    fn initial(players: Vec<AgentPubKeyB64>) -> Self;
+    // Returns error if the game can't be played with the given options and players
+    // By default, any options are valid
+    fn validate_options(
+        _options: &Self::GameOptions,
+        _players: &Vec<AgentPubKeyB64>,
+    ) -> ExternResult<()> {
+        Ok(())
+    }
+
+    // Constructs the initial state for the game, with the options it was created with
+    fn initial(players: Vec<AgentPubKeyB64>, options: Self::GameOptions) -> Self;
 
     // Applies the move to the game object, transforming it
     // If the move is invalid, it should return an error
     fn apply_move(self, game_move: Self::GameMove, author: AgentPubKeyB64) -> ExternResult<Self>;
 
+    // Applies the move to the game object with the randomness of the random beacon,
+    // which is None if no round of the beacon has been completed since the last game move
+    // Override it if your game needs dice rolls or shuffles; by default, it ignores the randomness
+    fn apply_move_with_rng(
+        self,
+        game_move: Self::GameMove,
+        author: AgentPubKeyB64,
+        _rng: Option<GameRng>,
+    ) -> ExternResult<Self> {
+        self.apply_move(game_move, author)
+    }
+
+    // Whether all players submit their moves simultaneously in rounds, instead of taking turns
+    // Games played in rounds receive all the moves of each round at once in apply_round
+    fn plays_in_rounds() -> bool {
+        false
+    }
+
+    // Applies all the moves of a round at once, after all players have revealed them
+    // Only called for games played in rounds
+    fn apply_round(self, _moves: BTreeMap<AgentPubKeyB64, Self::GameMove>) -> ExternResult<Self> {
+        Err(WasmError::Guest("This game is not played in rounds".into()))
+    }
+
     // Gets the status for the game
     fn status(&self) -> GameStatus;
+
+    // Gets the outcome of the game following its rules, given its players
+    // Return None if the game has not finished yet
+    fn outcome(&self, players: &Vec<AgentPubKeyB64>) -> Option<GameOutcome>;
+
+    // Returns the players that are allowed to make the next move, given the players of the game
+    // and the author of the last move (None if no move has been made yet)
+    // By default, players move in strict round-robin order following the players of the game
+    fn allowed_movers(
+        &self,
+        players: &Vec<AgentPubKeyB64>,
+        last_mover: Option<&AgentPubKeyB64>,
+    ) -> Vec<AgentPubKeyB64> {
+        round_robin_next_player(players, last_mover)
+            .into_iter()
+            .collect()
+    }
+}
+
+/**
+ * Returns the player that follows the last mover in the list of players,
+ * or the first player if no move has been made yet
+ */
+pub fn round_robin_next_player(
+    players: &Vec<AgentPubKeyB64>,
+    last_mover: Option<&AgentPubKeyB64>,
+) -> Option<AgentPubKeyB64> {
+    let maybe_last_player_index =
+        last_mover.and_then(|last_mover| players.iter().position(|p| p.eq(last_mover)));
+
+    // Get the index of the player whose turn it is
+    let player_index = match maybe_last_player_index {
+        Some(last_player_index) => (last_player_index + 1) % players.len(),
+        None => 0,
+    };
+
+    players.get(player_index).cloned()
 }
@@ -0,0 +1,158 @@
+use hdk::prelude::*;
+
+use crate::{
+    game::{validate_game_entry, GameEntry},
+    game_match::{validate_match_entry, MatchEntry},
+    game_move::{validate_game_move_entry, GameMoveEntry},
+    game_result::{validate_game_result_entry, GameResultEntry},
+    invitation::{validate_invitation_entry, Invitation},
+    matchmaking::{validate_game_request_entry, GameRequest},
+    ratings::{validate_rating_change_entry, RatingChangeEntry},
+    tournament::{
+        validate_tournament_entry, validate_tournament_round, TournamentEntry, TournamentRound,
+    },
+    turn_based_game::TurnBasedGame,
+};
+
+/**
+ * Entries defined by the mixin
+ */
+enum MixinEntryType {
+    GameMove,
+    GameResult,
+    RatingChange,
+    Invitation,
+    GameRequest,
+    TournamentRound,
+    Tournament,
+    Match,
+    Game,
+}
+
+/**
+ * Validates all the DHT operations that involve entries of the turn based game mixin
+ *
+ * Games, matches, tournaments, moves and rating changes can only be created: any update or delete of them is rejected
+ */
+pub fn validate_turn_based_game_op<G: TurnBasedGame>(
+    op: Op,
+) -> ExternResult<ValidateCallbackResult> {
+    match op {
+        Op::StoreEntry { header, entry } => {
+            let author = header.hashed.content.author().clone();
+            let timestamp = header.hashed.content.timestamp().clone();
+
+            let entry_type = match mixin_entry_type(header.hashed.content.entry_type())? {
+                Some(entry_type) => entry_type,
+                None => return Ok(ValidateCallbackResult::Valid),
+            };
+
+            match entry_type {
+                MixinEntryType::GameMove => validate_game_move_entry::<G>(
+                    author,
+                    timestamp,
+                    GameMoveEntry::try_from(&entry)?,
+                ),
+                MixinEntryType::GameResult => {
+                    validate_game_result_entry::<G>(author, GameResultEntry::try_from(&entry)?)
+                }
+                MixinEntryType::RatingChange => {
+                    validate_rating_change_entry::<G>(author, RatingChangeEntry::try_from(&entry)?)
+                }
+                MixinEntryType::Invitation => {
+                    validate_invitation_entry::<G>(author, Invitation::try_from(&entry)?)
+                }
+                MixinEntryType::GameRequest => {
+                    validate_game_request_entry::<G>(author, GameRequest::try_from(&entry)?)
+                }
+                MixinEntryType::TournamentRound => {
+                    validate_tournament_round(author, TournamentRound::try_from(&entry)?)
+                }
+                MixinEntryType::Tournament => {
+                    validate_tournament_entry::<G>(author, TournamentEntry::try_from(&entry)?)
+                }
+                MixinEntryType::Match => validate_match_entry::<G>(MatchEntry::try_from(&entry)?),
+                MixinEntryType::Game => {
+                    validate_game_entry::<G>(timestamp, GameEntry::try_from(&entry)?)
+                }
+            }
+        }
+        Op::RegisterUpdate {
+            original_header, ..
+        } => reject_turn_based_game_entry(original_header.entry_type(), "update"),
+        Op::RegisterDelete {
+            original_header, ..
+        } => reject_turn_based_game_entry(original_header.entry_type(), "delete"),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+/** Helper functions */
+
+/**
+ * Returns which entry of the mixin the given entry type is, looking up the id of its entry definition in the zome
+ *
+ * Returns None for entries that are not defined by the mixin, which the host zome validates
+ */
+fn mixin_entry_type(entry_type: &EntryType) -> ExternResult<Option<MixinEntryType>> {
+    let app_entry_type = match entry_type {
+        EntryType::App(app_entry_type) => app_entry_type,
+        _ => return Ok(None),
+    };
+
+    let zome_info = zome_info()?;
+
+    if !app_entry_type.zome_id().eq(&zome_info.id) {
+        return Ok(None);
+    }
+
+    let entry_def_id = match zome_info.entry_defs.0.get(app_entry_type.id().0 as usize) {
+        Some(entry_def) => entry_def.id.clone(),
+        None => return Ok(None),
+    };
+
+    let mixin_entry_types = vec![
+        (GameMoveEntry::entry_def().id, MixinEntryType::GameMove),
+        (GameResultEntry::entry_def().id, MixinEntryType::GameResult),
+        (
+            RatingChangeEntry::entry_def().id,
+            MixinEntryType::RatingChange,
+        ),
+        (Invitation::entry_def().id, MixinEntryType::Invitation),
+        (GameRequest::entry_def().id, MixinEntryType::GameRequest),
+        (
+            TournamentRound::entry_def().id,
+            MixinEntryType::TournamentRound,
+        ),
+        (TournamentEntry::entry_def().id, MixinEntryType::Tournament),
+        (MatchEntry::entry_def().id, MixinEntryType::Match),
+        (GameEntry::entry_def().id, MixinEntryType::Game),
+    ];
+
+    Ok(mixin_entry_types
+        .into_iter()
+        .find(|(id, _)| id.eq(&entry_def_id))
+        .map(|(_, mixin_entry_type)| mixin_entry_type))
+}
+
+fn reject_turn_based_game_entry(
+    entry_type: &EntryType,
+    action: &str,
+) -> ExternResult<ValidateCallbackResult> {
+    let entries = match mixin_entry_type(entry_type)? {
+        Some(MixinEntryType::GameMove) => "game moves",
+        Some(MixinEntryType::GameResult) => "game results",
+        Some(MixinEntryType::RatingChange) => "rating changes",
+        Some(MixinEntryType::Invitation) => "invitations",
+        Some(MixinEntryType::TournamentRound) => "tournament rounds",
+        Some(MixinEntryType::Tournament) => "tournaments",
+        Some(MixinEntryType::Match) => "matches",
+        Some(MixinEntryType::Game) => "games",
+        Some(MixinEntryType::GameRequest) | None => return Ok(ValidateCallbackResult::Valid),
+    };
+
+    Ok(ValidateCallbackResult::Invalid(format!(
+        "Cannot {} {}",
+        action, entries
+    )))
+}
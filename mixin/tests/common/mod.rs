@@ -0,0 +1,263 @@
+#![allow(dead_code)]
+
+use hc_mixin_turn_based_game::{
+    GameOutcome, GameRng, GameStatus, NoOptions, OutcomeReason, TurnBasedGame,
+};
+use hdk::prelude::holo_hash::AgentPubKeyB64;
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+
+/**
+ * Players take turns adding 1, 2 or 3 to a counter, and whoever reaches the target wins
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct Countdown {
+    pub count: u32,
+    pub target: u32,
+    pub winner: Option<AgentPubKeyB64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct Add(pub u32);
+
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct CountdownOptions {
+    pub target: u32,
+}
+
+impl Default for CountdownOptions {
+    fn default() -> Self {
+        CountdownOptions { target: 10 }
+    }
+}
+
+impl TurnBasedGame for Countdown {
+    type GameMove = Add;
+    type GameOptions = CountdownOptions;
+
+    fn min_players() -> Option<usize> {
+        Some(2)
+    }
+
+    fn max_players() -> Option<usize> {
+        None
+    }
+
+    fn validate_options(
+        options: &CountdownOptions,
+        _players: &Vec<AgentPubKeyB64>,
+    ) -> ExternResult<()> {
+        match options.target > 0 {
+            true => Ok(()),
+            false => Err(WasmError::Guest("The target must be positive".into())),
+        }
+    }
+
+    fn initial(_players: Vec<AgentPubKeyB64>, options: CountdownOptions) -> Self {
+        Countdown {
+            count: 0,
+            target: options.target,
+            winner: None,
+        }
+    }
+
+    fn apply_move(self, game_move: Add, author: AgentPubKeyB64) -> ExternResult<Self> {
+        if game_move.0 < 1 || game_move.0 > 3 {
+            return Err(WasmError::Guest("Can only add 1, 2 or 3".into()));
+        }
+
+        let count = (self.count + game_move.0).min(self.target);
+
+        Ok(Countdown {
+            count,
+            target: self.target,
+            winner: match count == self.target {
+                true => Some(author),
+                false => None,
+            },
+        })
+    }
+
+    fn status(&self) -> GameStatus {
+        match self.winner {
+            Some(_) => GameStatus::Finished,
+            None => GameStatus::Ongoing,
+        }
+    }
+
+    fn outcome(&self, players: &Vec<AgentPubKeyB64>) -> Option<GameOutcome> {
+        self.winner.clone().map(|winner| {
+            GameOutcome::victory(
+                vec![winner],
+                players,
+                OutcomeReason::GameRules("reached the target".into()),
+            )
+        })
+    }
+}
+
+/**
+ * Every round, all players secretly pick a number, and the ones that pick the highest number get a point
+ *
+ * Whoever gets 3 points first wins
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct HighestPick {
+    pub points: BTreeMap<AgentPubKeyB64, u32>,
+    pub rounds: u32,
+    pub winner: Option<AgentPubKeyB64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct Pick(pub u32);
+
+impl TurnBasedGame for HighestPick {
+    type GameMove = Pick;
+    type GameOptions = NoOptions;
+
+    fn min_players() -> Option<usize> {
+        Some(2)
+    }
+
+    fn max_players() -> Option<usize> {
+        None
+    }
+
+    fn validate_options(_options: &NoOptions, _players: &Vec<AgentPubKeyB64>) -> ExternResult<()> {
+        Ok(())
+    }
+
+    fn initial(players: Vec<AgentPubKeyB64>, _options: NoOptions) -> Self {
+        HighestPick {
+            points: players.into_iter().map(|p| (p, 0)).collect(),
+            rounds: 0,
+            winner: None,
+        }
+    }
+
+    fn apply_move(self, _game_move: Pick, _author: AgentPubKeyB64) -> ExternResult<Self> {
+        Err(WasmError::Guest("Picks are only made in rounds".into()))
+    }
+
+    fn plays_in_rounds() -> bool {
+        true
+    }
+
+    fn apply_round(self, moves: BTreeMap<AgentPubKeyB64, Pick>) -> ExternResult<Self> {
+        let highest = moves.values().map(|pick| pick.0).max().unwrap_or(0);
+        let mut points = self.points;
+
+        for (player, pick) in moves.iter() {
+            if pick.0 == highest {
+                *points.entry(player.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let winner = points
+            .iter()
+            .find(|(_, points)| **points >= 3)
+            .map(|(player, _)| player.clone());
+
+        Ok(HighestPick {
+            points,
+            rounds: self.rounds + 1,
+            winner,
+        })
+    }
+
+    fn status(&self) -> GameStatus {
+        match self.winner {
+            Some(_) => GameStatus::Finished,
+            None => GameStatus::Ongoing,
+        }
+    }
+
+    fn outcome(&self, players: &Vec<AgentPubKeyB64>) -> Option<GameOutcome> {
+        self.winner.clone().map(|winner| {
+            GameOutcome::victory(
+                vec![winner],
+                players,
+                OutcomeReason::GameRules("got 3 points".into()),
+            )
+        })
+    }
+}
+
+/**
+ * Players take turns rolling a die with the randomness of the random beacon, and whoever gets to 20 first wins
+ */
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
+pub struct DiceRace {
+    pub positions: BTreeMap<AgentPubKeyB64, u64>,
+    pub winner: Option<AgentPubKeyB64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct Roll;
+
+impl TurnBasedGame for DiceRace {
+    type GameMove = Roll;
+    type GameOptions = NoOptions;
+
+    fn min_players() -> Option<usize> {
+        Some(2)
+    }
+
+    fn max_players() -> Option<usize> {
+        None
+    }
+
+    fn validate_options(_options: &NoOptions, _players: &Vec<AgentPubKeyB64>) -> ExternResult<()> {
+        Ok(())
+    }
+
+    fn initial(players: Vec<AgentPubKeyB64>, _options: NoOptions) -> Self {
+        DiceRace {
+            positions: players.into_iter().map(|p| (p, 0)).collect(),
+            winner: None,
+        }
+    }
+
+    fn apply_move(self, game_move: Roll, author: AgentPubKeyB64) -> ExternResult<Self> {
+        self.apply_move_with_rng(game_move, author, None)
+    }
+
+    fn apply_move_with_rng(
+        self,
+        _game_move: Roll,
+        author: AgentPubKeyB64,
+        rng: Option<GameRng>,
+    ) -> ExternResult<Self> {
+        let mut rng = rng.ok_or(WasmError::Guest(
+            "Rolling needs a new round of the random beacon".into(),
+        ))?;
+
+        let mut positions = self.positions;
+        let position = positions.entry(author.clone()).or_insert(0);
+        *position += rng.roll_die(6);
+
+        let winner = match *position >= 20 {
+            true => Some(author),
+            false => None,
+        };
+
+        Ok(DiceRace { positions, winner })
+    }
+
+    fn status(&self) -> GameStatus {
+        match self.winner {
+            Some(_) => GameStatus::Finished,
+            None => GameStatus::Ongoing,
+        }
+    }
+
+    fn outcome(&self, players: &Vec<AgentPubKeyB64>) -> Option<GameOutcome> {
+        self.winner.clone().map(|winner| {
+            GameOutcome::victory(
+                vec![winner],
+                players,
+                OutcomeReason::GameRules("reached 20".into()),
+            )
+        })
+    }
+}
@@ -0,0 +1,378 @@
+mod common;
+
+use common::{Add, Countdown, CountdownOptions, DiceRace, Roll};
+use hc_mixin_turn_based_game::{
+    accept_takeback, commit_hidden_move, commit_seed, create_game, create_game_with_options,
+    create_move, get_game_moves, get_game_result_entry, get_game_state, get_my_current_games,
+    remove_current_game, request_takeback, reveal_move, reveal_seed, GameSettings, MockNetwork,
+    SignalPayload,
+};
+use hdk::prelude::holo_hash::*;
+use hdk::prelude::*;
+
+#[test]
+fn creating_a_game_makes_it_a_current_game_of_all_players() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    for player in players.iter() {
+        let current_games = network.as_agent(player, || get_my_current_games()).unwrap();
+        assert!(current_games.contains_key(&game_hash));
+    }
+
+    let signals = network.signals_received_by(&players[1]);
+    assert!(matches!(
+        signals.as_slice(),
+        [SignalPayload::GameStarted { game_hash: hash, .. }] if hash.eq(&game_hash)
+    ));
+    assert!(network.signals_received_by(&players[0]).is_empty());
+}
+
+#[test]
+fn moves_are_ordered_and_sent_to_the_rival() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    let first_move_hash = network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(game_hash.clone(), None, Add(3))
+        })
+        .unwrap();
+    let second_move_hash = network
+        .as_agent(&players[1], || {
+            create_move::<Countdown>(game_hash.clone(), Some(first_move_hash.clone()), Add(2))
+        })
+        .unwrap();
+
+    let moves = get_game_moves(game_hash.clone()).unwrap();
+    let move_hashes: Vec<_> = moves.iter().map(|m| m.header_hash.clone()).collect();
+    assert_eq!(move_hashes, vec![first_move_hash, second_move_hash.clone()]);
+
+    let state: Countdown = get_game_state(game_hash).unwrap();
+    assert_eq!(state.count, 5);
+
+    let signals = network.signals_received_by(&players[0]);
+    assert!(matches!(
+        signals.as_slice(),
+        [SignalPayload::NewMove(move_info)] if move_info.header_hash.eq(&second_move_hash)
+    ));
+}
+
+#[test]
+fn the_state_of_the_game_follows_its_last_move_after_a_takeback() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    let first_move_hash = network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(game_hash.clone(), None, Add(3))
+        })
+        .unwrap();
+    let request_hash = network
+        .as_agent(&players[0], || {
+            request_takeback::<Countdown>(game_hash.clone(), Some(first_move_hash.clone()), 1)
+        })
+        .unwrap();
+    let accept_hash = network
+        .as_agent(&players[1], || {
+            accept_takeback::<Countdown>(game_hash.clone(), Some(request_hash.clone()))
+        })
+        .unwrap();
+
+    let state: Countdown = get_game_state(game_hash.clone()).unwrap();
+    assert_eq!(state.count, 0);
+
+    // The reverted move is played again, starting from the state stored in the acceptance
+    network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(game_hash.clone(), Some(accept_hash.clone()), Add(2))
+        })
+        .unwrap();
+
+    let state: Countdown = get_game_state(game_hash).unwrap();
+    assert_eq!(state.count, 2);
+}
+
+#[test]
+fn moves_out_of_turn_or_without_the_previous_move_are_rejected() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    let out_of_turn = network.as_agent(&players[1], || {
+        create_move::<Countdown>(game_hash.clone(), None, Add(1))
+    });
+    assert!(out_of_turn.is_err());
+
+    network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(game_hash.clone(), None, Add(1))
+        })
+        .unwrap();
+
+    let without_previous_move = network.as_agent(&players[1], || {
+        create_move::<Countdown>(game_hash.clone(), None, Add(1))
+    });
+    assert!(without_previous_move.is_err());
+    assert_eq!(get_game_moves(game_hash).unwrap().len(), 1);
+}
+
+#[test]
+fn the_move_that_finishes_the_game_commits_its_result() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || {
+            create_game_with_options::<Countdown>(
+                players.clone(),
+                GameSettings::default(),
+                CountdownOptions { target: 3 },
+            )
+        })
+        .unwrap();
+
+    assert!(get_game_result_entry(game_hash.clone()).unwrap().is_none());
+
+    let last_move_hash = network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(game_hash.clone(), None, Add(3))
+        })
+        .unwrap();
+
+    let game_result = get_game_result_entry(game_hash.clone())
+        .unwrap()
+        .expect("The game should have a result");
+    assert_eq!(game_result.last_move_hash, last_move_hash);
+    assert_eq!(game_result.outcome.winners, vec![players[0].clone()]);
+
+    let after_finish = network.as_agent(&players[1], || {
+        create_move::<Countdown>(game_hash.clone(), Some(last_move_hash.clone()), Add(1))
+    });
+    assert!(after_finish.is_err());
+}
+
+#[test]
+fn removing_a_current_game_notifies_online_rivals() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    network
+        .as_agent(&players[0], || remove_current_game(game_hash.clone()))
+        .unwrap();
+
+    assert_eq!(
+        network.remote_calls_to(&players[1]),
+        vec![String::from("notify_remove_my_current_game")]
+    );
+
+    // The rival removes the game themselves, and tells their UI about it
+    let rival_signals = network.signals_emitted_by(&players[1]);
+    assert!(matches!(
+        rival_signals.as_slice(),
+        [SignalPayload::RemovedCurrentGame { game_hash: hash }] if hash.eq(&game_hash)
+    ));
+
+    for player in players.iter() {
+        let current_games = network.as_agent(player, || get_my_current_games()).unwrap();
+        assert!(current_games.is_empty());
+    }
+}
+
+#[test]
+fn removing_a_current_game_with_an_offline_rival_removes_it_for_them() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    network.set_online(&players[1], false);
+
+    network
+        .as_agent(&players[0], || remove_current_game(game_hash.clone()))
+        .unwrap();
+
+    assert_eq!(
+        network.remote_calls_to(&players[1]),
+        vec![String::from("notify_remove_my_current_game")]
+    );
+    assert!(network.signals_emitted_by(&players[1]).is_empty());
+
+    network.set_online(&players[1], true);
+
+    for player in players.iter() {
+        let current_games = network.as_agent(player, || get_my_current_games()).unwrap();
+        assert!(current_games.is_empty());
+    }
+}
+
+#[test]
+fn offline_agents_miss_remote_signals() {
+    let network = MockNetwork::new(3);
+    let players = network.agents();
+
+    network.set_online(&players[2], false);
+
+    network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    assert_eq!(network.signals_received_by(&players[1]).len(), 1);
+    assert!(network.signals_received_by(&players[2]).is_empty());
+}
+
+#[test]
+fn hidden_moves_are_applied_when_they_are_revealed() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+    let salt = vec![7; 16];
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    let commit_hash = network
+        .as_agent(&players[0], || {
+            commit_hidden_move::<Countdown>(game_hash.clone(), None, Add(3), salt.clone())
+        })
+        .unwrap();
+
+    let state: Countdown = get_game_state(game_hash.clone()).unwrap();
+    assert_eq!(state.count, 0);
+
+    let wrong_reveal = network.as_agent(&players[0], || {
+        reveal_move::<Countdown>(
+            game_hash.clone(),
+            Some(commit_hash.clone()),
+            commit_hash.clone(),
+            Add(2),
+            salt.clone(),
+        )
+    });
+    assert!(wrong_reveal.is_err());
+
+    network
+        .as_agent(&players[0], || {
+            reveal_move::<Countdown>(
+                game_hash.clone(),
+                Some(commit_hash.clone()),
+                commit_hash.clone(),
+                Add(3),
+                salt.clone(),
+            )
+        })
+        .unwrap();
+
+    let state: Countdown = get_game_state(game_hash.clone()).unwrap();
+    assert_eq!(state.count, 3);
+    assert_eq!(get_game_moves(game_hash).unwrap().len(), 2);
+}
+
+#[test]
+fn rolls_with_the_seed_of_a_round_of_the_random_beacon() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    // Without a round of the random beacon there is no randomness to roll with
+    let early_roll = network.as_agent(&players[0], || {
+        create_move::<DiceRace>(game_hash.clone(), None, Roll)
+    });
+    assert!(early_roll.is_err());
+
+    let mut last_move_hash = None;
+    for (player, secret) in players.iter().zip(vec![1, 2]) {
+        last_move_hash = Some(
+            network
+                .as_agent(player, || {
+                    commit_seed::<DiceRace>(game_hash.clone(), last_move_hash.clone(), vec![secret])
+                })
+                .unwrap(),
+        );
+    }
+    for (player, secret) in players.iter().zip(vec![1, 2]) {
+        last_move_hash = Some(
+            network
+                .as_agent(player, || {
+                    reveal_seed::<DiceRace>(game_hash.clone(), last_move_hash.clone(), vec![secret])
+                })
+                .unwrap(),
+        );
+    }
+
+    network
+        .as_agent(&players[0], || {
+            create_move::<DiceRace>(game_hash.clone(), last_move_hash.clone(), Roll)
+        })
+        .unwrap();
+
+    let state: DiceRace = get_game_state(game_hash).unwrap();
+    assert!(state.positions[&players[0]] > 0);
+}
+
+#[test]
+fn links_to_moves_of_other_games_are_not_part_of_the_game() {
+    let network = MockNetwork::new(2);
+    let players = network.agents();
+
+    let game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+    network.advance_time(chrono::Duration::seconds(1));
+    let other_game_hash = network
+        .as_agent(&players[0], || create_game(players.clone()))
+        .unwrap();
+
+    let other_move_hash = network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(other_game_hash.clone(), None, Add(3))
+        })
+        .unwrap();
+
+    // Someone links the move of the other game from this game
+    let other_move = get(HeaderHash::from(other_move_hash), GetOptions::default())
+        .unwrap()
+        .unwrap();
+    create_link(
+        EntryHash::from(game_hash.clone()),
+        other_move.header().entry_hash().unwrap().clone(),
+        LinkType(0),
+        LinkTag::new("game->move"),
+    )
+    .unwrap();
+
+    assert!(get_game_moves(game_hash.clone()).unwrap().is_empty());
+
+    network
+        .as_agent(&players[0], || {
+            create_move::<Countdown>(game_hash.clone(), None, Add(1))
+        })
+        .unwrap();
+
+    let state: Countdown = get_game_state(game_hash).unwrap();
+    assert_eq!(state.count, 1);
+}
@@ -0,0 +1,334 @@
+mod common;
+
+use common::{Add, Countdown, CountdownOptions, DiceRace, HighestPick, Pick, Roll};
+use hc_mixin_turn_based_game::{
+    move_commitment, simulated_players, GameSettings, GameSimulator, GameStatus, MoveType,
+    NoOptions, OutcomeReason, TimeControl,
+};
+use hdk::prelude::holo_hash::AgentPubKeyB64;
+use hdk::prelude::*;
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+
+#[test]
+fn plays_scripted_moves_in_turn_order() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<Countdown>::new(players.clone()).unwrap();
+
+    let states = simulator
+        .play_all(vec![
+            (players[0].clone(), Add(3)),
+            (players[1].clone(), Add(2)),
+            (players[0].clone(), Add(1)),
+        ])
+        .unwrap();
+
+    let counts: Vec<u32> = states.iter().map(|s| s.count).collect();
+    assert_eq!(counts, vec![3, 5, 6]);
+    assert_eq!(simulator.moves().len(), 3);
+    assert_eq!(simulator.allowed_movers(), vec![players[1].clone()]);
+    assert!(matches!(simulator.status(), GameStatus::Ongoing));
+    assert!(simulator.outcome().is_none());
+}
+
+#[test]
+fn rejects_moves_out_of_turn() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<Countdown>::new(players.clone()).unwrap();
+
+    simulator.assert_invalid(&players[1], Add(1));
+    simulator.play(&players[0], Add(1)).unwrap();
+    simulator.assert_invalid(&players[0], Add(1));
+
+    assert_eq!(simulator.state().count, 1);
+}
+
+#[test]
+fn rejects_moves_that_the_game_rejects_without_changing_the_state() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<Countdown>::new(players.clone()).unwrap();
+
+    let error = simulator.assert_invalid(&players[0], Add(4));
+
+    assert!(matches!(error, WasmError::Guest(message) if message == "Can only add 1, 2 or 3"));
+    assert_eq!(simulator.state().count, 0);
+    assert!(simulator.moves().is_empty());
+}
+
+#[test]
+fn finishes_the_game_with_its_outcome() {
+    let players = simulated_players(3);
+    let mut simulator = GameSimulator::<Countdown>::new_with_options(
+        players.clone(),
+        CountdownOptions { target: 5 },
+    )
+    .unwrap();
+
+    simulator
+        .play_all(vec![
+            (players[0].clone(), Add(2)),
+            (players[1].clone(), Add(1)),
+            (players[2].clone(), Add(2)),
+        ])
+        .unwrap();
+
+    assert!(matches!(simulator.status(), GameStatus::Finished));
+
+    let outcome = simulator.outcome().unwrap();
+    assert_eq!(outcome.winners, vec![players[2].clone()]);
+    assert_eq!(outcome.losers, vec![players[0].clone(), players[1].clone()]);
+
+    simulator.assert_invalid(&players[0], Add(1));
+}
+
+#[test]
+fn rejects_bad_number_of_players() {
+    assert!(GameSimulator::<Countdown>::new(simulated_players(1)).is_err());
+}
+
+#[test]
+fn rejects_repeated_players() {
+    let players = simulated_players(2);
+
+    assert!(GameSimulator::<Countdown>::new(vec![players[0].clone(), players[0].clone()]).is_err());
+}
+
+#[test]
+fn rejects_invalid_options() {
+    let players = simulated_players(2);
+
+    assert!(
+        GameSimulator::<Countdown>::new_with_options(players, CountdownOptions { target: 0 })
+            .is_err()
+    );
+}
+
+#[test]
+fn resigning_finishes_the_game_with_the_rivals_as_winners() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<Countdown>::new(players.clone()).unwrap();
+
+    simulator.play(&players[0], Add(1)).unwrap();
+    simulator.play_move(&players[0], MoveType::Resign).unwrap();
+
+    assert!(matches!(simulator.status(), GameStatus::Finished));
+
+    let outcome = simulator.outcome().unwrap();
+    assert_eq!(outcome.winners, vec![players[1].clone()]);
+    assert!(matches!(outcome.reason, OutcomeReason::Resignation));
+
+    simulator.assert_invalid(&players[1], Add(1));
+}
+
+#[test]
+fn draw_offers_can_only_be_answered_by_the_rivals() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<Countdown>::new(players.clone()).unwrap();
+
+    simulator
+        .play_moves(vec![
+            (players[0].clone(), MoveType::OfferDraw),
+            (players[1].clone(), MoveType::DeclineDraw),
+        ])
+        .unwrap();
+
+    // Meta-moves don't pass the turn
+    assert_eq!(simulator.allowed_movers(), vec![players[0].clone()]);
+
+    simulator
+        .play_move(&players[0], MoveType::OfferDraw)
+        .unwrap();
+    simulator.assert_invalid_move(&players[0], MoveType::AcceptDraw);
+    simulator
+        .play_move(&players[1], MoveType::AcceptDraw)
+        .unwrap();
+
+    let outcome = simulator.outcome().unwrap();
+    assert!(outcome.winners.is_empty());
+    assert!(matches!(outcome.reason, OutcomeReason::Agreement));
+}
+
+#[test]
+fn plays_simultaneous_rounds_with_sealed_moves() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<HighestPick>::new(players.clone()).unwrap();
+
+    let pick = |value: u32| SerializedBytes::try_from(Pick(value)).unwrap();
+    let salt = vec![7; 16];
+
+    // Games played in rounds don't take turns
+    simulator.assert_invalid(&players[0], Pick(1));
+
+    simulator
+        .play_moves(vec![
+            (
+                players[1].clone(),
+                MoveType::SealRoundMove {
+                    commitment: move_commitment(&pick(2), &salt).unwrap(),
+                },
+            ),
+            (
+                players[0].clone(),
+                MoveType::SealRoundMove {
+                    commitment: move_commitment(&pick(5), &salt).unwrap(),
+                },
+            ),
+        ])
+        .unwrap();
+
+    // The revealed move must be the sealed one
+    simulator.assert_invalid_move(
+        &players[0],
+        MoveType::RevealRoundMove {
+            game_move: pick(6),
+            salt: salt.clone(),
+        },
+    );
+
+    let states = simulator
+        .play_moves(vec![
+            (
+                players[0].clone(),
+                MoveType::RevealRoundMove {
+                    game_move: pick(5),
+                    salt: salt.clone(),
+                },
+            ),
+            (
+                players[1].clone(),
+                MoveType::RevealRoundMove {
+                    game_move: pick(2),
+                    salt: salt.clone(),
+                },
+            ),
+        ])
+        .unwrap();
+
+    // The round is only resolved once all the moves are revealed
+    assert_eq!(states[0].rounds, 0);
+    assert_eq!(states[1].rounds, 1);
+    assert_eq!(states[1].points[&players[0]], 1);
+    assert_eq!(states[1].points[&players[1]], 0);
+}
+
+/**
+ * Moves of a complete round of the random beacon, in which each player commits to and reveals the given secret
+ */
+fn beacon_round(players: &Vec<AgentPubKeyB64>, secret: u8) -> Vec<(AgentPubKeyB64, MoveType)> {
+    let commits = players.iter().map(|player| {
+        (
+            player.clone(),
+            MoveType::CommitSeed {
+                commitment: Sha256::digest(&[secret]).to_vec(),
+            },
+        )
+    });
+    let reveals = players.iter().map(|player| {
+        (
+            player.clone(),
+            MoveType::RevealSeed {
+                secret: vec![secret],
+            },
+        )
+    });
+
+    commits.chain(reveals).collect()
+}
+
+#[test]
+fn accepting_a_takeback_does_not_restore_the_used_seed() {
+    let players = simulated_players(2);
+    let mut simulator = GameSimulator::<DiceRace>::new(players.clone()).unwrap();
+
+    // Without a round of the random beacon there is no randomness to roll with
+    simulator.assert_invalid(&players[0], Roll);
+
+    simulator.play_moves(beacon_round(&players, 1)).unwrap();
+    let rolled = simulator.play(&players[0], Roll).unwrap();
+    assert!(rolled.positions[&players[0]] > 0);
+
+    let reverted = simulator
+        .play_moves(vec![
+            (players[1].clone(), MoveType::RequestTakeback { moves: 1 }),
+            (players[0].clone(), MoveType::AcceptTakeback),
+        ])
+        .unwrap();
+    assert_eq!(reverted[1].positions[&players[0]], 0);
+
+    // The seed was used by the reverted roll, so rolling again needs a new round
+    simulator.assert_invalid(&players[0], Roll);
+
+    simulator.play_moves(beacon_round(&players, 2)).unwrap();
+    simulator.play(&players[0], Roll).unwrap();
+}
+
+/**
+ * Moves of a complete round, in which each player seals and then reveals their pick
+ */
+fn picks_round(players: &Vec<AgentPubKeyB64>, picks: Vec<u32>) -> Vec<(AgentPubKeyB64, MoveType)> {
+    let salt = vec![7; 16];
+    let picks: Vec<SerializedBytes> = picks
+        .into_iter()
+        .map(|value| SerializedBytes::try_from(Pick(value)).unwrap())
+        .collect();
+
+    let seals = players.iter().zip(picks.iter()).map(|(player, pick)| {
+        (
+            player.clone(),
+            MoveType::SealRoundMove {
+                commitment: move_commitment(pick, &salt).unwrap(),
+            },
+        )
+    });
+    let reveals = players.iter().zip(picks.iter()).map(|(player, pick)| {
+        (
+            player.clone(),
+            MoveType::RevealRoundMove {
+                game_move: pick.clone(),
+                salt: salt.clone(),
+            },
+        )
+    });
+
+    seals.chain(reveals).collect()
+}
+
+#[test]
+fn the_clock_of_games_played_in_rounds_restarts_with_each_round() {
+    let players = simulated_players(2);
+    let settings = GameSettings {
+        time_control: Some(TimeControl {
+            move_time_limit_ms: Some(60_000),
+            time_bank_ms: None,
+            increment_ms: 0,
+        }),
+        inactivity_threshold_ms: None,
+    };
+    let mut simulator =
+        GameSimulator::<HighestPick>::new_with_settings(players.clone(), NoOptions, settings)
+            .unwrap();
+
+    simulator.advance_time(chrono::Duration::seconds(50));
+    simulator
+        .play_moves(picks_round(&players, vec![1, 2]))
+        .unwrap();
+
+    // 100 seconds after the start of the game, but only 50 after the start of the round
+    simulator.advance_time(chrono::Duration::seconds(50));
+    let next_round = picks_round(&players, vec![3, 1]);
+    simulator
+        .play_move(&players[0], next_round[0].1.clone())
+        .unwrap();
+
+    // The round is waiting for the second player, whose time runs out
+    simulator.advance_time(chrono::Duration::seconds(20));
+    simulator.assert_invalid_move(&players[1], next_round[1].1.clone());
+    simulator
+        .play_move(&players[0], MoveType::ClaimTimeoutVictory)
+        .unwrap();
+
+    assert_eq!(
+        simulator.outcome().unwrap().winners,
+        vec![players[0].clone()]
+    );
+}
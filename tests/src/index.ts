@@ -51,6 +51,9 @@ const getWinner = (caller) => (gameHash) =>
 const getState = (caller) => (gameHash) =>
   caller.call("tictactoe", "get_game_state", gameHash);
 
+const getStatus = (caller) => (gameHash) =>
+  caller.call("tictactoe", "get_game_status", gameHash);
+
 const sleep = (ms) =>
   new Promise((resolve) => setTimeout(() => resolve(null), ms));
 
@@ -97,7 +100,7 @@ orchestrator.registerScenario("add and retrieve a book", async (s, t) => {
   result = await getState(alice)(gameAddress);
   t.deepEqual(result.player_1[1], []);
   t.deepEqual(result.player_2[1], []);
-  t.deepEqual(result.player_resigned, null);
+  t.equal(await getStatus(alice)(gameAddress), "Ongoing");
 
   //8
   try {
@@ -165,7 +168,7 @@ orchestrator.registerScenario("add and retrieve a book", async (s, t) => {
     { x: 1, y: 0 },
     { x: 1, y: 1 },
   ]);
-  t.deepEqual(result.player_resigned, null);
+  t.equal(await getStatus(alice)(gameAddress), "Finished");
 
   try {
     result = await createMove(alice)(gameAddress, lastMoveHash, 2, 2);
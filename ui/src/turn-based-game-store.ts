@@ -125,13 +125,15 @@ export class TurnBasedGameStore<M> {
 
     const move_entry: GameMoveEntry<M> = {
       author_pub_key: this.myAgentPubKey,
+      signed_by: this.myAgentPubKey,
       game_hash: gameHash,
-      game_move: move,
+      game_move: { Game: move },
       previous_move_hash: previousMoveHash,
     };
     const m: MoveInfo<M> = {
       header_hash: undefined as any,
       game_move_entry: move_entry,
+      reverted: false,
     };
 
     this.#gamesByEntryHash.update(games => {
@@ -185,6 +187,7 @@ export class TurnBasedGameStore<M> {
       games[gameHash].moves = moves.map(m => ({
         header_hash: m.header_hash,
         game_move_entry: this.decodeMove(m.game_move_entry),
+        reverted: m.reverted,
       }));
 
       return games;
@@ -223,6 +226,7 @@ export class TurnBasedGameStore<M> {
       games[gameMove.game_hash].moves.push({
         header_hash: moveHeaderHash,
         game_move_entry: move,
+        reverted: false,
       });
 
       return games;
@@ -245,10 +249,17 @@ export class TurnBasedGameStore<M> {
     //  });
   }
 
+  // Only the moves defined by the game are serialized by it, meta-moves are passed through as they are
   private decodeMove(move: GameMoveEntry<any>): GameMoveEntry<M> {
-    return {
-      ...move,
-      game_move: decode(move.game_move) as any,
-    };
+    const gameMove = move.game_move;
+
+    if (typeof gameMove === 'object' && 'Game' in gameMove) {
+      return {
+        ...move,
+        game_move: { Game: decode(gameMove.Game) as M },
+      };
+    }
+
+    return move;
   }
 }
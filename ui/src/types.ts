@@ -1,18 +1,89 @@
-import { AgentPubKeyB64, HeaderHashB64 } from '@holochain-open-dev/core-types';
+import {
+  AgentPubKeyB64,
+  Dictionary,
+  EntryHashB64,
+  HeaderHashB64,
+} from '@holochain-open-dev/core-types';
+
+export interface TimeControl {
+  move_time_limit_ms: number | undefined;
+  time_bank_ms: number | undefined;
+  increment_ms: number;
+}
+
+export interface GameSettings {
+  time_control: TimeControl | undefined;
+  inactivity_threshold_ms: number | undefined;
+}
 
 export interface GameEntry {
   players: Array<AgentPubKeyB64>;
   created_at: number;
+  settings: GameSettings;
+  // Rest of the members of each seat in team games
+  teammates: Dictionary<Array<AgentPubKeyB64>>;
+  options: Uint8Array | undefined;
+  match_hash: EntryHashB64 | undefined;
+  previous_match_result: EntryHashB64 | undefined;
+}
+
+// Moves of the mixin: either a move defined by the game, or one of the meta-moves
+export type MoveType<M> =
+  | { Game: M }
+  | 'ClaimTimeoutVictory'
+  | 'ClaimForfeit'
+  | 'Resign'
+  | 'OfferDraw'
+  | 'AcceptDraw'
+  | 'DeclineDraw'
+  | { RequestTakeback: { moves: number } }
+  | 'AcceptTakeback'
+  | 'DeclineTakeback'
+  | { CommitMove: { commitment: Uint8Array } }
+  | {
+      RevealMove: {
+        commit_move_hash: HeaderHashB64;
+        game_move: Uint8Array;
+        salt: Uint8Array;
+      };
+    }
+  | { CommitSeed: { commitment: Uint8Array } }
+  | { RevealSeed: { secret: Uint8Array } }
+  | { SealRoundMove: { commitment: Uint8Array } }
+  | { RevealRoundMove: { game_move: Uint8Array; salt: Uint8Array } };
+
+export interface MoveClock {
+  turn_started_at: number;
+  time_banks_ms: Dictionary<number>;
+}
+
+// State of the protocols of the mixin in the moment a move was made
+export interface MoveContext {
+  random_beacon: any;
+  round: any;
+  last_game_move_hash: HeaderHashB64 | undefined;
+  last_game_mover: AgentPubKeyB64 | undefined;
+  game_moves_count: number;
+  open_commitments: Dictionary<HeaderHashB64>;
+  requested_takeback: number | undefined;
+  last_game_move_at: number | undefined;
+  last_move_at: Dictionary<number>;
 }
 
 export interface GameMoveEntry<M> {
   game_hash: string;
   author_pub_key: AgentPubKeyB64;
-  game_move: M;
+  signed_by?: AgentPubKeyB64;
+  game_move: MoveType<M>;
+  resulting_game_state?: Uint8Array;
   previous_move_hash: HeaderHashB64 | undefined;
+  clock?: MoveClock;
+  context?: MoveContext;
 }
 
 export interface MoveInfo<M> {
   header_hash: string;
   game_move_entry: GameMoveEntry<M>;
+  // Whether the move was reverted by an accepted takeback
+  reverted: boolean;
 }